@@ -1,9 +1,12 @@
-use actix_web::web::{get, Bytes, Json, JsonConfig};
+use actix_web::web::{Bytes, Json};
 use actix_web::{get, post, App, HttpRequest, HttpResponse, HttpServer, Responder};
 use dotenvy::dotenv;
 use tracing::debug;
-use wechat_pay_rust_sdk::model::{H5Params, H5SceneInfo, WechatPayDecodeData, WechatPayNotify};
+use wechat_pay_rust_sdk::model::{
+    H5Params, H5SceneInfo, NativeParams, WechatPayDecodeData, WechatPayNotify,
+};
 use wechat_pay_rust_sdk::pay::{PayNotifyTrait, WechatPay};
+use wechat_pay_rust_sdk::qrcode::QrOptions;
 use wechat_pay_rust_sdk::util;
 
 #[post("/pay/notify")]
@@ -37,11 +40,8 @@ async fn pay_notify3(bytes: Bytes, req: HttpRequest) -> impl Responder {
     let wechatpay_nonce = headers.get("wechatpay-nonce").unwrap().to_str().unwrap();
     let body = String::from_utf8(bytes.to_vec()).unwrap();
     let wechat_pay = WechatPay::from_env();
-    wechat_pay
+    let _ = wechat_pay
         .h5_pay(H5Params::new(
-            "appid",
-            "mchid",
-            "notify_url",
             "测试支付1分",
             util::random_trade_no().as_str(),
             1.into(),
@@ -64,6 +64,29 @@ async fn pay_notify3(bytes: Bytes, req: HttpRequest) -> impl Responder {
     }))
 }
 
+/// 下单后在终端打印二维码，并返回PNG图片给扫码设备展示
+#[get("/pay/native")]
+async fn native_qrcode() -> impl Responder {
+    dotenv().ok();
+    let wechat_pay = WechatPay::from_env();
+    let response = wechat_pay
+        .native_pay(NativeParams::new(
+            "测试支付1分",
+            util::random_trade_no().as_str(),
+            1.into(),
+        ))
+        .await
+        .unwrap();
+    match response.qrcode_terminal(&QrOptions::default().with_margin(2)) {
+        Ok(terminal) => println!("{}", terminal),
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    }
+    match response.qrcode_png(&QrOptions::default().with_size(300)) {
+        Ok(png) => HttpResponse::Ok().content_type("image/png").body(png),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
+
 #[get("/")]
 async fn home() -> impl Responder {
     HttpResponse::Ok().body("hello rust")
@@ -108,6 +131,7 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .service(pay_notify)
             .service(pay_notify2)
+            .service(native_qrcode)
             .service(home)
     })
     .bind(("0.0.0.0", 8080))?
@@ -0,0 +1,704 @@
+//! 特约商户进件，提交申请单、查询申请状态、修改和查询结算账户。
+//! 姓名、证件号码、手机号、邮箱和银行账号等字段以明文构建，提交时使用平台证书加密。
+use crate::error::PayError;
+use crate::macros::params_builder;
+use crate::response::ResponseTrait;
+use crate::sign::PlatformPublicKey;
+use serde::{Deserialize, Serialize};
+
+fn encrypt(key: &PlatformPublicKey, value: &mut String) -> Result<(), PayError> {
+    *value = key.encrypt(value.as_str())?;
+    Ok(())
+}
+
+fn encrypt_option(key: &PlatformPublicKey, value: &mut Option<String>) -> Result<(), PayError> {
+    if let Some(value) = value {
+        encrypt(key, value)?;
+    }
+    Ok(())
+}
+
+/// 超级管理员信息
+#[derive(Serialize, Debug, Clone)]
+pub struct ContactInfo {
+    ///【超级管理员类型】 LEGAL：经营者/法人，SUPER：经办人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_type: Option<String>,
+    ///【超级管理员姓名】 加密
+    pub contact_name: String,
+    ///【超级管理员证件类型】 经办人时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_id_doc_type: Option<String>,
+    ///【超级管理员身份证件号码】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_id_number: Option<String>,
+    ///【超级管理员证件正面照片】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_id_doc_copy: Option<String>,
+    ///【超级管理员证件反面照片】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_id_doc_copy_back: Option<String>,
+    ///【超级管理员证件有效期开始时间】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_period_begin: Option<String>,
+    ///【超级管理员证件有效期结束时间】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_period_end: Option<String>,
+    ///【业务办理授权函】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_authorization_letter: Option<String>,
+    ///【联系手机】 加密
+    pub mobile_phone: String,
+    ///【联系邮箱】 加密
+    pub contact_email: String,
+}
+
+impl ContactInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.contact_name)?;
+        encrypt_option(key, &mut self.contact_id_number)?;
+        encrypt(key, &mut self.mobile_phone)?;
+        encrypt(key, &mut self.contact_email)
+    }
+}
+
+/// 营业执照
+#[derive(Serialize, Debug, Clone)]
+pub struct BusinessLicenseInfo {
+    ///【营业执照照片】 media_id
+    pub license_copy: String,
+    ///【注册号/统一社会信用代码】
+    pub license_number: String,
+    ///【商户名称】
+    pub merchant_name: String,
+    ///【个体户经营者/法人姓名】
+    pub legal_person: String,
+    ///【注册地址】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_address: Option<String>,
+    ///【有效期限开始日期】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period_begin: Option<String>,
+    ///【有效期限结束日期】 长期时填写“长期”
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period_end: Option<String>,
+}
+
+/// 登记证书，主体为政府机关、事业单位、社会组织时填写
+#[derive(Serialize, Debug, Clone)]
+pub struct CertificateInfo {
+    ///【登记证书照片】 media_id
+    pub cert_copy: String,
+    ///【登记证书类型】
+    pub cert_type: String,
+    ///【证书号】
+    pub cert_number: String,
+    ///【商户名称】
+    pub merchant_name: String,
+    ///【注册地址】
+    pub company_address: String,
+    ///【法定代表人】
+    pub legal_person: String,
+    ///【有效期限开始日期】
+    pub period_begin: String,
+    ///【有效期限结束日期】
+    pub period_end: String,
+}
+
+/// 身份证信息
+#[derive(Serialize, Debug, Clone)]
+pub struct IdCardInfo {
+    ///【身份证人像面照片】 media_id
+    pub id_card_copy: String,
+    ///【身份证国徽面照片】 media_id
+    pub id_card_national: String,
+    ///【身份证姓名】 加密
+    pub id_card_name: String,
+    ///【身份证号码】 加密
+    pub id_card_number: String,
+    ///【身份证居住地址】 加密，主体为企业时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_card_address: Option<String>,
+    ///【身份证有效期开始时间】
+    pub card_period_begin: String,
+    ///【身份证有效期结束时间】
+    pub card_period_end: String,
+}
+
+impl IdCardInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.id_card_name)?;
+        encrypt(key, &mut self.id_card_number)?;
+        encrypt_option(key, &mut self.id_card_address)
+    }
+}
+
+/// 其他类型证件信息
+#[derive(Serialize, Debug, Clone)]
+pub struct IdDocInfo {
+    ///【证件正面照片】 media_id
+    pub id_doc_copy: String,
+    ///【证件反面照片】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_doc_copy_back: Option<String>,
+    ///【证件姓名】 加密
+    pub id_doc_name: String,
+    ///【证件号码】 加密
+    pub id_doc_number: String,
+    ///【证件居住地址】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_doc_address: Option<String>,
+    ///【证件有效期开始时间】
+    pub doc_period_begin: String,
+    ///【证件有效期结束时间】
+    pub doc_period_end: String,
+}
+
+impl IdDocInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.id_doc_name)?;
+        encrypt(key, &mut self.id_doc_number)?;
+        encrypt_option(key, &mut self.id_doc_address)
+    }
+}
+
+/// 经营者/法人身份证件
+#[derive(Serialize, Debug, Clone)]
+pub struct IdentityInfo {
+    ///【证件持有人类型】 LEGAL：经营者/法人，SUPER：经办人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_holder_type: Option<String>,
+    ///【证件类型】 IDENTIFICATION_TYPE_IDCARD等
+    pub id_doc_type: String,
+    ///【法定代表人说明函】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorize_letter_copy: Option<String>,
+    ///【身份证信息】 证件类型为身份证时填写
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_card_info: Option<IdCardInfo>,
+    ///【其他类型证件信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_doc_info: Option<IdDocInfo>,
+    ///【经营者/法人是否为受益人】 主体为企业时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<bool>,
+}
+
+impl IdentityInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        if let Some(info) = &mut self.id_card_info {
+            info.encrypt(key)?;
+        }
+        if let Some(info) = &mut self.id_doc_info {
+            info.encrypt(key)?;
+        }
+        Ok(())
+    }
+}
+
+/// 最终受益人信息
+#[derive(Serialize, Debug, Clone)]
+pub struct UboInfo {
+    ///【证件类型】
+    pub ubo_id_doc_type: String,
+    ///【证件正面照片】 media_id
+    pub ubo_id_doc_copy: String,
+    ///【证件反面照片】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ubo_id_doc_copy_back: Option<String>,
+    ///【受益人姓名】 加密
+    pub ubo_id_doc_name: String,
+    ///【证件号码】 加密
+    pub ubo_id_doc_number: String,
+    ///【证件居住地址】 加密
+    pub ubo_id_doc_address: String,
+    ///【证件有效期开始时间】
+    pub ubo_period_begin: String,
+    ///【证件有效期结束时间】
+    pub ubo_period_end: String,
+}
+
+impl UboInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.ubo_id_doc_name)?;
+        encrypt(key, &mut self.ubo_id_doc_number)?;
+        encrypt(key, &mut self.ubo_id_doc_address)
+    }
+}
+
+/// 主体资料
+#[derive(Serialize, Debug, Clone)]
+pub struct SubjectInfo {
+    ///【主体类型】 SUBJECT_TYPE_INDIVIDUAL、SUBJECT_TYPE_ENTERPRISE等
+    pub subject_type: String,
+    ///【是否是金融机构】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finance_institution: Option<bool>,
+    ///【营业执照】 主体为个体户、企业时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_license_info: Option<BusinessLicenseInfo>,
+    ///【登记证书】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_info: Option<CertificateInfo>,
+    ///【单位证明函照片】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_letter_copy: Option<String>,
+    ///【经营者/法人身份证件】
+    pub identity_info: IdentityInfo,
+    ///【最终受益人信息列表】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ubo_info_list: Option<Vec<UboInfo>>,
+}
+
+impl SubjectInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        self.identity_info.encrypt(key)?;
+        for ubo in self.ubo_info_list.iter_mut().flatten() {
+            ubo.encrypt(key)?;
+        }
+        Ok(())
+    }
+}
+
+/// 线下场所
+#[derive(Serialize, Debug, Clone)]
+pub struct BizStoreInfo {
+    ///【线下场所名称】
+    pub biz_store_name: String,
+    ///【线下场所省市编码】
+    pub biz_address_code: String,
+    ///【线下场所地址】
+    pub biz_store_address: String,
+    ///【线下场所门头照片】 media_id列表
+    pub store_entrance_pic: Vec<String>,
+    ///【线下场所内部照片】 media_id列表
+    pub indoor_pic: Vec<String>,
+    ///【线下场所对应的商家AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biz_sub_appid: Option<String>,
+}
+
+/// 公众号、小程序场景
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AppletInfo {
+    ///【服务商的AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp_appid: Option<String>,
+    ///【商家的AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp_sub_appid: Option<String>,
+    ///【页面截图】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp_pics: Option<Vec<String>>,
+}
+
+/// 小程序场景
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MiniProgramInfo {
+    ///【服务商的小程序AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_program_appid: Option<String>,
+    ///【商家的小程序AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_program_sub_appid: Option<String>,
+    ///【小程序截图】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_program_pics: Option<Vec<String>>,
+}
+
+/// APP场景
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AppInfo {
+    ///【服务商应用AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_appid: Option<String>,
+    ///【商家应用AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_sub_appid: Option<String>,
+    ///【APP截图】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_pics: Option<Vec<String>>,
+}
+
+/// 互联网网站场景
+#[derive(Serialize, Debug, Clone)]
+pub struct WebInfo {
+    ///【互联网网站域名】
+    pub domain: String,
+    ///【网站授权函】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_authorisation: Option<String>,
+    ///【互联网网站对应的商家AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_appid: Option<String>,
+}
+
+/// 经营场景
+#[derive(Serialize, Debug, Clone)]
+pub struct SalesInfo {
+    ///【经营场景类型】 SALES_SCENES_STORE、SALES_SCENES_MP、SALES_SCENES_MINI_PROGRAM等
+    pub sales_scenes_type: Vec<String>,
+    ///【线下场所场景】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biz_store_info: Option<BizStoreInfo>,
+    ///【公众号场景】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mp_info: Option<AppletInfo>,
+    ///【小程序场景】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_program_info: Option<MiniProgramInfo>,
+    ///【APP场景】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_info: Option<AppInfo>,
+    ///【互联网网站场景】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_info: Option<WebInfo>,
+}
+
+/// 经营资料
+#[derive(Serialize, Debug, Clone)]
+pub struct BusinessInfo {
+    ///【商户简称】 在支付完成页向买家展示
+    pub merchant_shortname: String,
+    ///【客服电话】
+    pub service_phone: String,
+    ///【经营场景】
+    pub sales_info: SalesInfo,
+}
+
+/// 结算规则
+#[derive(Serialize, Debug, Clone)]
+pub struct SettlementInfo {
+    ///【入驻结算规则ID】
+    pub settlement_id: String,
+    ///【所属行业】
+    pub qualification_type: String,
+    ///【特殊资质图片】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualifications: Option<Vec<String>>,
+    ///【优惠费率活动ID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activities_id: Option<String>,
+    ///【优惠费率活动值】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activities_rate: Option<String>,
+    ///【优惠费率活动补充材料】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activities_additions: Option<Vec<String>>,
+}
+
+/// 结算银行账户
+#[derive(Serialize, Debug, Clone)]
+pub struct BankAccountInfo {
+    ///【账户类型】 BANK_ACCOUNT_TYPE_CORPORATE：对公，BANK_ACCOUNT_TYPE_PERSONAL：对私
+    pub bank_account_type: String,
+    ///【开户名称】 加密
+    pub account_name: String,
+    ///【开户银行】
+    pub account_bank: String,
+    ///【开户银行省市编码】
+    pub bank_address_code: String,
+    ///【开户银行联行号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_branch_id: Option<String>,
+    ///【开户银行全称（含支行）】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_name: Option<String>,
+    ///【银行账号】 加密
+    pub account_number: String,
+}
+
+impl BankAccountInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.account_name)?;
+        encrypt(key, &mut self.account_number)
+    }
+}
+
+/// 补充材料
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AdditionInfo {
+    ///【法人开户承诺函】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legal_person_commitment: Option<String>,
+    ///【法人开户意愿视频】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legal_person_video: Option<String>,
+    ///【补充材料】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_addition_pics: Option<Vec<String>>,
+    ///【补充说明】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_addition_msg: Option<String>,
+}
+
+/// 提交特约商户进件申请单，敏感字段填写明文，提交时使用平台证书加密
+#[derive(Serialize, Debug, Clone)]
+pub struct ApplymentParams {
+    ///【业务申请编号】 服务商自定义的唯一编号
+    pub business_code: String,
+    ///【超级管理员信息】
+    pub contact_info: ContactInfo,
+    ///【主体资料】
+    pub subject_info: SubjectInfo,
+    ///【经营资料】
+    pub business_info: BusinessInfo,
+    ///【结算规则】
+    pub settlement_info: SettlementInfo,
+    ///【结算银行账户】
+    pub bank_account_info: BankAccountInfo,
+    ///【补充材料】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addition_info: Option<AdditionInfo>,
+}
+
+params_builder! {
+    ApplymentParamsBuilder => ApplymentParams {
+        required {
+            business_code: String,
+            contact_info: ContactInfo,
+            subject_info: SubjectInfo,
+            business_info: BusinessInfo,
+            settlement_info: SettlementInfo,
+            bank_account_info: BankAccountInfo,
+        }
+        optional {
+            addition_info: AdditionInfo,
+        }
+        defaults {}
+    }
+}
+
+impl ApplymentParams {
+    /// 使用平台证书加密所有敏感字段
+    pub(crate) fn encrypt(mut self, key: &PlatformPublicKey) -> Result<Self, PayError> {
+        self.contact_info.encrypt(key)?;
+        self.subject_info.encrypt(key)?;
+        self.bank_account_info.encrypt(key)?;
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApplymentResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【微信支付申请单号】
+    pub applyment_id: Option<u64>,
+}
+
+impl ResponseTrait for ApplymentResponse {}
+
+/// 申请单状态
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum ApplymentState {
+    /// 编辑中
+    #[serde(rename = "APPLYMENT_STATE_EDITTING")]
+    Editing,
+    /// 审核中
+    #[serde(rename = "APPLYMENT_STATE_AUDITING")]
+    Auditing,
+    /// 已驳回
+    #[serde(rename = "APPLYMENT_STATE_REJECTED")]
+    Rejected,
+    /// 待账户验证
+    #[serde(rename = "APPLYMENT_STATE_TO_BE_CONFIRMED")]
+    ToBeConfirmed,
+    /// 待签约
+    #[serde(rename = "APPLYMENT_STATE_TO_BE_SIGNED")]
+    ToBeSigned,
+    /// 开通权限中
+    #[serde(rename = "APPLYMENT_STATE_SIGNING")]
+    Signing,
+    /// 已完成
+    #[serde(rename = "APPLYMENT_STATE_FINISHED")]
+    Finished,
+    /// 已作废
+    #[serde(rename = "APPLYMENT_STATE_CANCELED")]
+    Canceled,
+    #[serde(other)]
+    Unknown,
+}
+
+/// 驳回原因
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditDetail {
+    ///【字段名】
+    pub field: Option<String>,
+    ///【字段名称】
+    pub field_name: Option<String>,
+    ///【驳回原因】
+    pub reject_reason: Option<String>,
+}
+
+/// 申请单状态
+#[derive(Debug, Deserialize)]
+pub struct ApplymentStateResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【业务申请编号】
+    pub business_code: Option<String>,
+    ///【微信支付申请单号】
+    pub applyment_id: Option<u64>,
+    ///【特约商户号】 申请单完成后返回
+    pub sub_mchid: Option<String>,
+    ///【超级管理员签约链接】
+    pub sign_url: Option<String>,
+    ///【申请单状态】
+    pub applyment_state: Option<ApplymentState>,
+    ///【申请状态描述】
+    pub applyment_state_msg: Option<String>,
+    ///【驳回原因详情】
+    #[serde(default)]
+    pub audit_detail: Vec<AuditDetail>,
+}
+
+impl ResponseTrait for ApplymentStateResponse {}
+
+/// 修改结算账户
+#[derive(Serialize, Debug, Clone)]
+pub struct ModifySettlementParams {
+    ///【修改模式】 MODIFY_MODE_ASYNC：异步修改
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modify_mode: Option<String>,
+    ///【账户类型】 ACCOUNT_TYPE_BUSINESS：对公，ACCOUNT_TYPE_PRIVATE：对私
+    pub account_type: String,
+    ///【开户名称】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_name: Option<String>,
+    ///【开户银行】
+    pub account_bank: String,
+    ///【开户银行省市编码】
+    pub bank_address_code: String,
+    ///【开户银行全称（含支行）】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_name: Option<String>,
+    ///【开户银行联行号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_branch_id: Option<String>,
+    ///【银行账号】 加密
+    pub account_number: String,
+}
+
+params_builder! {
+    ModifySettlementParamsBuilder => ModifySettlementParams {
+        required {
+            account_type: String,
+            account_bank: String,
+            bank_address_code: String,
+            account_number: String,
+        }
+        optional {
+            modify_mode: String,
+            account_name: String,
+            bank_name: String,
+            bank_branch_id: String,
+        }
+        defaults {}
+    }
+}
+
+impl ModifySettlementParams {
+    /// 使用平台证书加密开户名称和银行账号
+    pub(crate) fn encrypt(mut self, key: &PlatformPublicKey) -> Result<Self, PayError> {
+        encrypt_option(key, &mut self.account_name)?;
+        encrypt(key, &mut self.account_number)?;
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModifySettlementResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【修改结算账户申请单号】 异步修改时返回
+    pub application_no: Option<String>,
+}
+
+impl ResponseTrait for ModifySettlementResponse {}
+
+/// 结算账户，银行账号只返回掩码
+#[derive(Debug, Deserialize)]
+pub struct SettlementAccount {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【账户类型】
+    pub account_type: Option<String>,
+    ///【开户银行】
+    pub account_bank: Option<String>,
+    ///【开户银行全称（含支行）】
+    pub bank_name: Option<String>,
+    ///【开户银行联行号】
+    pub bank_branch_id: Option<String>,
+    ///【银行账号】 掩码
+    pub account_number: Option<String>,
+    ///【汇款验证结果】 VERIFY_SUCCESS、VERIFY_FAIL、VERIFYING
+    pub verify_result: Option<String>,
+    ///【汇款验证失败原因】
+    pub verify_fail_reason: Option<String>,
+}
+
+impl ResponseTrait for SettlementAccount {}
+
+#[cfg(test)]
+mod tests {
+    use crate::applyment::{
+        ApplymentState, ApplymentStateResponse, BankAccountInfo, ModifySettlementParams,
+    };
+    use crate::sign::{self, PlatformPublicKey};
+
+    #[test]
+    fn test_encrypt_settlement() {
+        let certificate = include_str!("../testdata/apiclient_cert.pem");
+        let private_key = include_str!("../testdata/apiclient_key.pem");
+        let key = PlatformPublicKey::from_certificate(certificate).unwrap();
+        assert_eq!(key.serial_no(), "5157F09EFDC096DE15EBE81A47057A7232F1B8E1");
+
+        let params = ModifySettlementParams::builder()
+            .account_type("ACCOUNT_TYPE_PRIVATE")
+            .account_bank("工商银行")
+            .bank_address_code("110000")
+            .account_name("张三")
+            .account_number("6222021234567890123")
+            .build()
+            .unwrap()
+            .encrypt(&key)
+            .unwrap();
+        assert_eq!(
+            sign::rsa_oaep_decrypt(private_key, &params.account_number).unwrap(),
+            "6222021234567890123"
+        );
+        assert_eq!(
+            sign::rsa_oaep_decrypt(private_key, params.account_name.as_ref().unwrap()).unwrap(),
+            "张三"
+        );
+
+        let mut account = BankAccountInfo {
+            bank_account_type: "BANK_ACCOUNT_TYPE_PERSONAL".to_string(),
+            account_name: "张三".to_string(),
+            account_bank: "工商银行".to_string(),
+            bank_address_code: "110000".to_string(),
+            bank_branch_id: None,
+            bank_name: None,
+            account_number: "6222021234567890123".to_string(),
+        };
+        account.encrypt(&key).unwrap();
+        assert_ne!(account.account_number, "6222021234567890123");
+        assert_eq!(account.account_bank, "工商银行");
+    }
+
+    #[test]
+    fn test_applyment_state() {
+        let response: ApplymentStateResponse = serde_json::from_str(
+            r#"{"business_code":"1900013511_10000","applyment_id":2000002124775691,
+            "applyment_state":"APPLYMENT_STATE_REJECTED","applyment_state_msg":"请修改",
+            "audit_detail":[{"field":"id_card_number","field_name":"身份证号码","reject_reason":"号码有误"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.applyment_state, Some(ApplymentState::Rejected));
+        assert_eq!(
+            response.audit_detail[0].field.as_deref(),
+            Some("id_card_number")
+        );
+    }
+}
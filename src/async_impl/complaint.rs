@@ -0,0 +1,119 @@
+use crate::complaint::{
+    ComplaintCompleteParams, ComplaintDetail, ComplaintListParams, ComplaintNotifyUrlParams,
+    ComplaintNotifyUrlResponse, ComplaintPage, ComplaintResponseParams, NegotiationHistory,
+    UpdateRefundProgressParams,
+};
+use crate::error::PayError;
+use crate::pay::WechatPay;
+use crate::request::HttpMethod;
+
+impl WechatPay {
+    /// 查询投诉单列表
+    pub async fn list_complaints(
+        &self,
+        params: &ComplaintListParams,
+    ) -> Result<ComplaintPage<ComplaintDetail>, PayError> {
+        let url = format!("/v3/merchant-service/complaints-v2?{}", params.to_query()?);
+        self.get_pay(url.as_str()).await
+    }
+
+    /// 查询投诉单详情
+    pub async fn complaint_detail(
+        &self,
+        complaint_id: impl AsRef<str>,
+    ) -> Result<ComplaintDetail, PayError> {
+        let url = format!(
+            "/v3/merchant-service/complaints-v2/{}",
+            complaint_id.as_ref()
+        );
+        self.get_pay(url.as_str()).await
+    }
+
+    /// 查询投诉协商历史
+    pub async fn complaint_negotiation_history(
+        &self,
+        complaint_id: impl AsRef<str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<ComplaintPage<NegotiationHistory>, PayError> {
+        let url = format!(
+            "/v3/merchant-service/complaints-v2/{}/negotiation-historys?limit={}&offset={}",
+            complaint_id.as_ref(),
+            limit,
+            offset
+        );
+        self.get_pay(url.as_str()).await
+    }
+
+    /// 回复用户
+    pub async fn respond_complaint(
+        &self,
+        complaint_id: impl AsRef<str>,
+        params: ComplaintResponseParams,
+    ) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/merchant-service/complaints-v2/{}/response",
+            complaint_id.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), params)
+            .await
+    }
+
+    /// 反馈处理完成
+    pub async fn complete_complaint(&self, complaint_id: impl AsRef<str>) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/merchant-service/complaints-v2/{}/complete",
+            complaint_id.as_ref()
+        );
+        self.pay_no_content(
+            HttpMethod::POST,
+            url.as_str(),
+            ComplaintCompleteParams::default(),
+        )
+        .await
+    }
+
+    /// 更新退款审批结果
+    pub async fn update_complaint_refund_progress(
+        &self,
+        complaint_id: impl AsRef<str>,
+        params: UpdateRefundProgressParams,
+    ) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/merchant-service/complaints-v2/{}/update-refund-progress",
+            complaint_id.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), params)
+            .await
+    }
+
+    /// 创建投诉通知回调地址
+    pub async fn create_complaint_notify_url(
+        &self,
+        params: ComplaintNotifyUrlParams,
+    ) -> Result<ComplaintNotifyUrlResponse, PayError> {
+        let url = "/v3/merchant-service/complaint-notifications";
+        self.pay(HttpMethod::POST, url, params).await
+    }
+
+    /// 查询投诉通知回调地址
+    pub async fn query_complaint_notify_url(&self) -> Result<ComplaintNotifyUrlResponse, PayError> {
+        let url = "/v3/merchant-service/complaint-notifications";
+        self.get_pay(url).await
+    }
+
+    /// 更新投诉通知回调地址
+    pub async fn update_complaint_notify_url(
+        &self,
+        params: ComplaintNotifyUrlParams,
+    ) -> Result<ComplaintNotifyUrlResponse, PayError> {
+        let url = "/v3/merchant-service/complaint-notifications";
+        self.pay(HttpMethod::PUT, url, params).await
+    }
+
+    /// 删除投诉通知回调地址
+    pub async fn delete_complaint_notify_url(&self) -> Result<(), PayError> {
+        let url = "/v3/merchant-service/complaint-notifications";
+        self.delete_pay(url).await
+    }
+}
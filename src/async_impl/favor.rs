@@ -0,0 +1,128 @@
+use crate::error::PayError;
+use crate::favor::{
+    CreateStockParams, CreateStockResponse, FavorCallbackParams, FavorCallbackResponse,
+    FlowDownloadResponse, SendCouponParams, SendCouponResponse, StockCreatorParams, StockDetail,
+    StockStateResponse, UserCouponsParams, UserCouponsResponse,
+};
+use crate::pay::{WechatPay, WechatPayTrait};
+use crate::request::HttpMethod;
+
+impl WechatPay {
+    /// 创建代金券批次
+    pub async fn create_coupon_stock(
+        &self,
+        params: CreateStockParams,
+    ) -> Result<CreateStockResponse, PayError> {
+        let url = "/v3/marketing/favor/coupon-stocks";
+        self.pay(HttpMethod::POST, url, params).await
+    }
+
+    /// 激活代金券批次
+    pub async fn start_coupon_stock(
+        &self,
+        stock_id: impl AsRef<str>,
+    ) -> Result<StockStateResponse, PayError> {
+        let url = format!("/v3/marketing/favor/stocks/{}/start", stock_id.as_ref());
+        self.pay(
+            HttpMethod::POST,
+            url.as_str(),
+            StockCreatorParams::default(),
+        )
+        .await
+    }
+
+    /// 暂停代金券批次
+    pub async fn pause_coupon_stock(
+        &self,
+        stock_id: impl AsRef<str>,
+    ) -> Result<StockStateResponse, PayError> {
+        let url = format!("/v3/marketing/favor/stocks/{}/pause", stock_id.as_ref());
+        self.pay(
+            HttpMethod::POST,
+            url.as_str(),
+            StockCreatorParams::default(),
+        )
+        .await
+    }
+
+    /// 查询代金券批次详情
+    pub async fn query_coupon_stock(
+        &self,
+        stock_id: impl AsRef<str>,
+    ) -> Result<StockDetail, PayError> {
+        let url = format!(
+            "/v3/marketing/favor/stocks/{}?stock_creator_mchid={}",
+            stock_id.as_ref(),
+            self.mch_id()
+        );
+        self.get_pay(url.as_str()).await
+    }
+
+    /// 向用户发放代金券
+    pub async fn send_coupon(
+        &self,
+        openid: impl AsRef<str>,
+        params: SendCouponParams,
+    ) -> Result<SendCouponResponse, PayError> {
+        let url = format!("/v3/marketing/favor/users/{}/coupons", openid.as_ref());
+        self.pay(HttpMethod::POST, url.as_str(), params).await
+    }
+
+    /// 根据商户号查询用户的代金券
+    pub async fn query_user_coupons(
+        &self,
+        openid: impl AsRef<str>,
+        params: &UserCouponsParams,
+    ) -> Result<UserCouponsResponse, PayError> {
+        let url = format!(
+            "/v3/marketing/favor/users/{}/coupons?{}",
+            openid.as_ref(),
+            params.to_query(&self.appid())
+        );
+        self.get_pay(url.as_str()).await
+    }
+
+    /// 下载批次核销明细，返回校验过的CSV文件内容
+    pub async fn download_stock_use_flow(
+        &self,
+        stock_id: impl AsRef<str>,
+    ) -> Result<Vec<u8>, PayError> {
+        let url = format!("/v3/marketing/favor/stocks/{}/use-flow", stock_id.as_ref());
+        self.download_flow(url.as_str()).await
+    }
+
+    /// 下载批次退款明细，返回校验过的CSV文件内容
+    pub async fn download_stock_refund_flow(
+        &self,
+        stock_id: impl AsRef<str>,
+    ) -> Result<Vec<u8>, PayError> {
+        let url = format!(
+            "/v3/marketing/favor/stocks/{}/refund-flow",
+            stock_id.as_ref()
+        );
+        self.download_flow(url.as_str()).await
+    }
+
+    async fn download_flow(&self, url: &str) -> Result<Vec<u8>, PayError> {
+        let flow: FlowDownloadResponse = self.get_pay(url).await?;
+        let download_url = flow.url.as_deref().ok_or_else(|| {
+            PayError::WechatError(format!(
+                "{}: {}",
+                flow.code.clone().unwrap_or_default(),
+                flow.message.clone().unwrap_or_default()
+            ))
+        })?;
+        let content = self.download(download_url).await?;
+        flow.verify(&content)?;
+        Ok(content)
+    }
+
+    /// 设置代金券核销消息通知地址
+    pub async fn set_coupon_callback(
+        &self,
+        params: FavorCallbackParams,
+    ) -> Result<FavorCallbackResponse, PayError> {
+        let url = "/v3/marketing/favor/callbacks";
+        self.pay(HttpMethod::POST, url, params).await
+    }
+}
@@ -0,0 +1,58 @@
+use crate::debug;
+use crate::error::PayError;
+use crate::media::{MediaType, MediaUpload};
+use crate::pay::{WechatPay, WechatPayTrait};
+use crate::request::HttpMethod;
+use crate::response::MediaUploadResponse;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::multipart::{Form, Part};
+
+impl WechatPay {
+    /// 上传图片或视频，签名使用meta部分的JSON
+    pub async fn upload_media(
+        &self,
+        media_type: MediaType,
+        filename: impl AsRef<str>,
+        content: Vec<u8>,
+    ) -> Result<MediaUploadResponse, PayError> {
+        let upload = MediaUpload::new(media_type, filename.as_ref(), content)?;
+        let url = media_type.url();
+        let mut headers = self.build_header(HttpMethod::POST, url, upload.meta.as_str())?;
+        // multipart的Content-Type由reqwest生成
+        headers.remove(CONTENT_TYPE);
+        let url = format!("{}{}", self.base_url(), url);
+        debug!("url: {} meta: {}", url, upload.meta);
+        let meta = Part::text(upload.meta).mime_str("application/json")?;
+        let file = Part::bytes(upload.content)
+            .file_name(upload.filename)
+            .mime_str(upload.content_type)?;
+        let form = Form::new().part("meta", meta).part("file", file);
+        self.client()
+            .post(url)
+            .headers(headers)
+            .multipart(form)
+            .send()
+            .await?
+            .json::<MediaUploadResponse>()
+            .await
+            .map(Ok)?
+    }
+
+    /// 上传图片，支持JPG、BMP、PNG，不超过2M
+    pub async fn upload_image(
+        &self,
+        filename: impl AsRef<str>,
+        content: Vec<u8>,
+    ) -> Result<MediaUploadResponse, PayError> {
+        self.upload_media(MediaType::Image, filename, content).await
+    }
+
+    /// 上传视频，不超过5M
+    pub async fn upload_video(
+        &self,
+        filename: impl AsRef<str>,
+        content: Vec<u8>,
+    ) -> Result<MediaUploadResponse, PayError> {
+        self.upload_media(MediaType::Video, filename, content).await
+    }
+}
@@ -0,0 +1,21 @@
+use crate::error::PayError;
+use crate::registry::{self, MerchantRegistry};
+
+impl MerchantRegistry {
+    /// 下载并保存商户的平台证书，返回证书数量
+    pub async fn refresh_platform_certificates(&mut self, mch_id: &str) -> Result<usize, PayError> {
+        let wechat_pay = self.merchant(mch_id)?;
+        let response = wechat_pay.certificates().await?;
+        let certificates = registry::decode_certificates(wechat_pay, response)?;
+        self.set_platform_certificates(mch_id, certificates)
+    }
+
+    /// 下载并保存所有商户的平台证书
+    pub async fn refresh_all_platform_certificates(&mut self) -> Result<(), PayError> {
+        let mch_ids = self.mch_ids().map(String::from).collect::<Vec<_>>();
+        for mch_id in mch_ids {
+            self.refresh_platform_certificates(&mch_id).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,590 @@
+use crate::datetime;
+use crate::error::PayError;
+use crate::pay_type::{BankType, PayType, TradeState};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+/// 账单中的一行数据，可按表头名称取值
+#[derive(Debug, Clone)]
+pub struct BillRow<'a> {
+    columns: &'a HashMap<String, usize>,
+    fields: Vec<String>,
+    line: usize,
+}
+
+impl<'a> BillRow<'a> {
+    /// 按表头名称取值，空字符串视为不存在
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.columns
+            .get(name)
+            .and_then(|index| self.fields.get(*index))
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 当前行在账单文件中的行号（从1开始）
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    fn required(&self, name: &str) -> Result<&str, PayError> {
+        self.get(name)
+            .ok_or_else(|| self.error(format!("missing column {}", name)))
+    }
+
+    fn error(&self, message: String) -> PayError {
+        PayError::BillError(format!("line {}: {}", self.line, message))
+    }
+
+    fn time(&self, name: &str) -> Result<DateTime<FixedOffset>, PayError> {
+        parse_time(self.required(name)?).map_err(|e| self.error(format!("{}: {}", name, e)))
+    }
+
+    fn time_opt(&self, name: &str) -> Result<Option<DateTime<FixedOffset>>, PayError> {
+        self.get(name)
+            .map(|value| parse_time(value).map_err(|e| self.error(format!("{}: {}", name, e))))
+            .transpose()
+    }
+
+    fn cents(&self, name: &str) -> Result<i64, PayError> {
+        parse_cents(self.required(name)?).map_err(|e| self.error(format!("{}: {}", name, e)))
+    }
+
+    fn cents_opt(&self, name: &str) -> Result<Option<i64>, PayError> {
+        self.get(name)
+            .map(|value| parse_cents(value).map_err(|e| self.error(format!("{}: {}", name, e))))
+            .transpose()
+    }
+
+    /// 退款单号等字段在无退款时会填充为0
+    fn id_opt(&self, name: &str) -> Option<String> {
+        self.get(name)
+            .filter(|value| *value != "0")
+            .map(|value| value.to_string())
+    }
+
+    fn string(&self, name: &str) -> Result<String, PayError> {
+        self.required(name).map(|value| value.to_string())
+    }
+
+    fn string_opt(&self, name: &str) -> Option<String> {
+        self.get(name).map(|value| value.to_string())
+    }
+}
+
+/// 可以从账单行解析出来的记录类型
+pub trait BillRecord: Sized {
+    fn from_row(row: &BillRow) -> Result<Self, PayError>;
+}
+
+/// 交易账单记录，兼容ALL、SUCCESS、REFUND三种账单类型，
+/// 不同账单类型缺少的列以`None`表示。金额单位均为分。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeBillRecord {
+    ///【交易时间】
+    pub trade_time: DateTime<FixedOffset>,
+    ///【公众账号ID】
+    pub appid: String,
+    ///【商户号】
+    pub mchid: String,
+    ///【特约商户号】 服务商模式下的子商户号
+    pub sub_mchid: Option<String>,
+    ///【设备号】
+    pub device_id: Option<String>,
+    ///【微信订单号】
+    pub transaction_id: String,
+    ///【商户订单号】
+    pub out_trade_no: String,
+    ///【用户标识】
+    pub openid: Option<String>,
+    ///【交易类型】
+    pub trade_type: PayType,
+    ///【交易状态】
+    pub trade_state: TradeState,
+    ///【付款银行】
+    pub bank_type: Option<BankType>,
+    ///【货币种类】
+    pub currency: String,
+    ///【应结订单金额】
+    pub settlement_total: i64,
+    ///【代金券金额】
+    pub coupon_amount: i64,
+    ///【退款申请时间】 仅REFUND账单
+    pub refund_apply_time: Option<DateTime<FixedOffset>>,
+    ///【退款成功时间】 仅REFUND账单
+    pub refund_success_time: Option<DateTime<FixedOffset>>,
+    ///【微信退款单号】
+    pub refund_id: Option<String>,
+    ///【商户退款单号】
+    pub out_refund_no: Option<String>,
+    ///【退款金额】
+    pub refund_amount: Option<i64>,
+    ///【充值券退款金额】
+    pub coupon_refund_amount: Option<i64>,
+    ///【退款类型】
+    pub refund_type: Option<String>,
+    ///【退款状态】
+    pub refund_status: Option<String>,
+    ///【商品名称】
+    pub goods_name: Option<String>,
+    ///【商户数据包】
+    pub attach: Option<String>,
+    ///【手续费】
+    pub fee: i64,
+    ///【费率】 例如`0.60%`
+    pub fee_rate: Option<String>,
+    ///【订单金额】
+    pub order_amount: Option<i64>,
+    ///【申请退款金额】
+    pub apply_refund_amount: Option<i64>,
+    ///【费率备注】
+    pub fee_rate_remark: Option<String>,
+}
+
+impl TradeBillRecord {
+    /// 是否为退款记录
+    pub fn is_refund(&self) -> bool {
+        self.out_refund_no.is_some() || self.refund_id.is_some()
+    }
+}
+
+impl BillRecord for TradeBillRecord {
+    fn from_row(row: &BillRow) -> Result<Self, PayError> {
+        Ok(Self {
+            trade_time: row.time("交易时间")?,
+            appid: row.string("公众账号ID")?,
+            mchid: row.string("商户号")?,
+            sub_mchid: row.id_opt("特约商户号"),
+            device_id: row.string_opt("设备号"),
+            transaction_id: row.string("微信订单号")?,
+            out_trade_no: row.string("商户订单号")?,
+            openid: row.string_opt("用户标识"),
+            trade_type: row.required("交易类型")?.into(),
+            trade_state: row.required("交易状态")?.into(),
+            bank_type: row.get("付款银行").map(BankType::from),
+            currency: row.string("货币种类")?,
+            settlement_total: row.cents("应结订单金额")?,
+            coupon_amount: row.cents_opt("代金券金额")?.unwrap_or_default(),
+            refund_apply_time: row.time_opt("退款申请时间")?,
+            refund_success_time: row.time_opt("退款成功时间")?,
+            refund_id: row.id_opt("微信退款单号"),
+            out_refund_no: row.id_opt("商户退款单号"),
+            refund_amount: row.cents_opt("退款金额")?,
+            coupon_refund_amount: row.cents_opt("充值券退款金额")?,
+            refund_type: row.string_opt("退款类型"),
+            refund_status: row.string_opt("退款状态"),
+            goods_name: row.string_opt("商品名称"),
+            attach: row.string_opt("商户数据包"),
+            fee: row.cents_opt("手续费")?.unwrap_or_default(),
+            fee_rate: row.string_opt("费率"),
+            order_amount: row.cents_opt("订单金额")?,
+            apply_refund_amount: row.cents_opt("申请退款金额")?,
+            fee_rate_remark: row.string_opt("费率备注"),
+        })
+    }
+}
+
+/// 资金收支类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FundDirection {
+    ///收入
+    Income,
+    ///支出
+    Expense,
+}
+
+/// 资金账单记录，金额单位均为分
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FundFlowRecord {
+    ///【记账时间】
+    pub booking_time: DateTime<FixedOffset>,
+    ///【微信支付业务单号】
+    pub business_order_no: String,
+    ///【资金流水单号】
+    pub flow_id: String,
+    ///【业务名称】
+    pub business_name: String,
+    ///【业务类型】
+    pub business_type: String,
+    ///【收支类型】
+    pub direction: FundDirection,
+    ///【收支金额】
+    pub amount: i64,
+    ///【账户结余】
+    pub balance: i64,
+    ///【资金变更提交申请人】
+    pub applicant: Option<String>,
+    ///【备注】
+    pub remark: Option<String>,
+    ///【业务凭证号】
+    pub voucher_no: Option<String>,
+}
+
+impl BillRecord for FundFlowRecord {
+    fn from_row(row: &BillRow) -> Result<Self, PayError> {
+        let direction = match row.required("收支类型")? {
+            "收入" => FundDirection::Income,
+            "支出" => FundDirection::Expense,
+            other => return Err(row.error(format!("unknown fund direction {}", other))),
+        };
+        Ok(Self {
+            booking_time: row.time("记账时间")?,
+            business_order_no: row.string("微信支付业务单号")?,
+            flow_id: row.string("资金流水单号")?,
+            business_name: row.string("业务名称")?,
+            business_type: row.string("业务类型")?,
+            direction,
+            amount: row.cents("收支金额（元）")?,
+            balance: row.cents("账户结余（元）")?,
+            applicant: row.string_opt("资金变更提交申请人"),
+            remark: row.string_opt("备注"),
+            voucher_no: row.string_opt("业务凭证号"),
+        })
+    }
+}
+
+/// 账单末尾的汇总数据，交易账单和资金账单各自只会填充对应的字段。金额单位均为分。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BillSummary {
+    ///【总交易单数】或【资金流水总笔数】
+    pub total_count: u64,
+    ///【应结订单总金额】
+    pub settlement_total: Option<i64>,
+    ///【退款总金额】
+    pub refund_total: Option<i64>,
+    ///【充值券退款总金额】
+    pub coupon_refund_total: Option<i64>,
+    ///【手续费总金额】
+    pub fee_total: Option<i64>,
+    ///【订单总金额】
+    pub order_total: Option<i64>,
+    ///【申请退款总金额】
+    pub apply_refund_total: Option<i64>,
+    ///【收入笔数】
+    pub income_count: Option<u64>,
+    ///【收入金额】
+    pub income_amount: Option<i64>,
+    ///【支出笔数】
+    pub expense_count: Option<u64>,
+    ///【支出金额】
+    pub expense_amount: Option<i64>,
+}
+
+impl BillSummary {
+    fn from_row(row: &BillRow) -> Result<Self, PayError> {
+        let count = |name: &str| -> Result<Option<u64>, PayError> {
+            row.get(name)
+                .map(|value| {
+                    value
+                        .parse::<u64>()
+                        .map_err(|e| row.error(format!("{}: {}", name, e)))
+                })
+                .transpose()
+        };
+        let total_count = match count("总交易单数")? {
+            Some(total_count) => total_count,
+            None => count("资金流水总笔数")?.unwrap_or_default(),
+        };
+        Ok(Self {
+            total_count,
+            settlement_total: row.cents_opt("应结订单总金额")?,
+            refund_total: row.cents_opt("退款总金额")?,
+            coupon_refund_total: row.cents_opt("充值券退款总金额")?,
+            fee_total: row.cents_opt("手续费总金额")?,
+            order_total: row.cents_opt("订单总金额")?,
+            apply_refund_total: row.cents_opt("申请退款总金额")?,
+            income_count: count("收入笔数")?,
+            income_amount: row.cents_opt("收入金额")?,
+            expense_count: count("支出笔数")?,
+            expense_amount: row.cents_opt("支出金额")?,
+        })
+    }
+}
+
+/// 流式账单解析器，逐行读取，不需要把整个账单读入内存。
+/// 可以接收任意`BufRead`，例如下载得到的`&[u8]`或`BufReader<File>`。
+/// ```rust
+/// use wechat_pay_rust_sdk::bill::{BillReader, TradeBillRecord};
+///
+/// let bytes = "交易时间,公众账号ID,商户号,特约商户号,设备号,微信订单号,商户订单号,用户标识,交易类型,交易状态,付款银行,货币种类,应结订单金额,代金券金额,商品名称,商户数据包,手续费,费率,订单金额,费率备注\n\
+/// `2024-01-12 10:36:13,`wx15f4803f25xxxxx,`1639718111,`0,`,`4200001926202401125681342683,`8e289eebd1f44604b0b27e05f11bcf10,`oAZUY6DittOj59wCzPn6vNgpK2eY,`MWEB,`SUCCESS,`OTHERS,`CNY,`0.01,`0.00,`测试支付1分,`,`0.00000,`0.60%,`0.01,`\n\
+/// 总交易单数,应结订单总金额,退款总金额,充值券退款总金额,手续费总金额,订单总金额,申请退款总金额\n\
+/// `1,`0.01,`0.00,`0.00,`0.00000,`0.01,`0.00\n";
+/// let mut reader = BillReader::<_, TradeBillRecord>::new(bytes.as_bytes());
+/// let records = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(records[0].settlement_total, 1);
+/// assert_eq!(reader.summary().unwrap().total_count, 1);
+/// ```
+pub struct BillReader<R, T> {
+    reader: R,
+    columns: Option<HashMap<String, usize>>,
+    summary: Option<BillSummary>,
+    line: usize,
+    finished: bool,
+    _record: PhantomData<T>,
+}
+
+/// 交易账单解析器
+pub type TradeBillReader<R> = BillReader<R, TradeBillRecord>;
+
+/// 资金账单解析器
+pub type FundFlowBillReader<R> = BillReader<R, FundFlowRecord>;
+
+impl<R: BufRead, T: BillRecord> BillReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            columns: None,
+            summary: None,
+            line: 0,
+            finished: false,
+            _record: PhantomData,
+        }
+    }
+
+    /// 账单汇总数据，读取完所有明细之后才可用
+    pub fn summary(&self) -> Option<&BillSummary> {
+        self.summary.as_ref()
+    }
+
+    /// 读取完所有明细，返回汇总数据
+    pub fn into_summary(mut self) -> Result<Option<BillSummary>, PayError> {
+        for record in self.by_ref() {
+            record?;
+        }
+        Ok(self.summary)
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>, PayError> {
+        loop {
+            let mut line = String::new();
+            let size = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|e| PayError::BillError(format!("line {}: {}", self.line + 1, e)))?;
+            if size == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            let line = line
+                .trim_start_matches('\u{feff}')
+                .trim_end_matches(['\r', '\n']);
+            if !line.trim().is_empty() {
+                return Ok(Some(line.to_string()));
+            }
+        }
+    }
+
+    fn read_summary(&mut self, header: &str) -> Result<(), PayError> {
+        let columns = header_columns(header);
+        let values = self.read_line()?.ok_or_else(|| {
+            PayError::BillError(format!("line {}: missing summary values", self.line))
+        })?;
+        let row = BillRow {
+            columns: &columns,
+            fields: split_fields(&values),
+            line: self.line,
+        };
+        self.summary = Some(BillSummary::from_row(&row)?);
+        Ok(())
+    }
+
+    fn next_record(&mut self) -> Result<Option<T>, PayError> {
+        if self.columns.is_none() {
+            match self.read_line()? {
+                Some(header) => self.columns = Some(header_columns(&header)),
+                None => return Ok(None),
+            }
+        }
+        let line = match self.read_line()? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        // 明细行的每个字段都以`开头，汇总表头则没有
+        if !line.starts_with('`') {
+            self.read_summary(&line)?;
+            return Ok(None);
+        }
+        let row = BillRow {
+            columns: self.columns.as_ref().unwrap(),
+            fields: split_fields(&line),
+            line: self.line,
+        };
+        T::from_row(&row).map(Some)
+    }
+}
+
+impl<R: BufRead, T: BillRecord> Iterator for BillReader<R, T> {
+    type Item = Result<T, PayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record = self.next_record();
+        if !matches!(record, Ok(Some(_))) {
+            self.finished = true;
+        }
+        record.transpose()
+    }
+}
+
+fn header_columns(header: &str) -> HashMap<String, usize> {
+    header
+        .split(',')
+        .enumerate()
+        .map(|(index, name)| (name.trim().to_string(), index))
+        .collect()
+}
+
+/// 字段之间以",`"分隔，商品名称等字段中出现的逗号不会被误切分
+fn split_fields(line: &str) -> Vec<String> {
+    line.strip_prefix('`')
+        .unwrap_or(line)
+        .split(",`")
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+/// 解析账单中的时间，账单时间均为东八区时间
+fn parse_time(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map_err(|e| format!("invalid time {}: {}", value, e))?;
+    datetime::shanghai()
+        .from_local_datetime(&naive)
+        .single()
+        .ok_or_else(|| format!("invalid time {}", value))
+}
+
+/// 把以元为单位的金额转换为分，手续费等字段可能带有多于两位的小数，按四舍五入处理
+pub(crate) fn parse_cents(value: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid amount {}", value);
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if integer.is_empty() || !integer.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let yuan = integer.parse::<i64>().map_err(|_| invalid())?;
+    let mut fraction_digits = fraction.bytes().map(|b| (b - b'0') as i64);
+    let jiao = fraction_digits.next().unwrap_or(0);
+    let fen = fraction_digits.next().unwrap_or(0);
+    let round = fraction_digits
+        .next()
+        .map_or(0, |digit| (digit >= 5) as i64);
+    let cents = yuan
+        .checked_mul(100)
+        .and_then(|cents| cents.checked_add(jiao * 10 + fen + round))
+        .ok_or_else(invalid)?;
+    Ok(if negative { -cents } else { cents })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bill::{
+        parse_cents, FundDirection, FundFlowBillReader, TradeBillReader, TradeBillRecord,
+    };
+
+    const TRADE_BILL: &str = "\u{feff}交易时间,公众账号ID,商户号,特约商户号,设备号,微信订单号,商户订单号,用户标识,交易类型,交易状态,付款银行,货币种类,应结订单金额,代金券金额,微信退款单号,商户退款单号,退款金额,充值券退款金额,退款类型,退款状态,商品名称,商户数据包,手续费,费率,订单金额,申请退款金额,费率备注\r
+`2024-01-12 10:36:13,`wx15f4803f25xxxxx,`1639718111,`0,`,`4200001926202401125681342683,`8e289eebd1f44604b0b27e05f11bcf10,`oAZUY6DittOj59wCzPn6vNgpK2eY,`MWEB,`SUCCESS,`OTHERS,`CNY,`12.34,`0.00,`0,`0,`0.00,`0.00,`,`,`T恤,尺码L,`,`0.07000,`0.60%,`12.34,`0.00,`\r
+`2024-01-12 11:02:45,`wx15f4803f25xxxxx,`1639718111,`0,`,`4200001926202401125681342683,`8e289eebd1f44604b0b27e05f11bcf10,`oAZUY6DittOj59wCzPn6vNgpK2eY,`MWEB,`REFUND,`OTHERS,`CNY,`0.00,`0.00,`50302108032024011211111111111,`R20240112001,`2.00,`0.00,`ORIGINAL,`SUCCESS,`T恤,尺码L,`,`-0.01000,`0.60%,`0.00,`2.00,`\r
+总交易单数,应结订单总金额,退款总金额,充值券退款总金额,手续费总金额,订单总金额,申请退款总金额\r
+`2,`12.34,`2.00,`0.00,`0.06000,`12.34,`2.00\r
+";
+
+    const FUND_FLOW_BILL: &str = "记账时间,微信支付业务单号,资金流水单号,业务名称,业务类型,收支类型,收支金额（元）,账户结余（元）,资金变更提交申请人,备注,业务凭证号
+`2024-01-12 10:36:13,`4200001926202401125681342683,`4200001926202401125681342683,`交易,`交易,`收入,`12.34,`112.34,`system,`,`
+`2024-01-13 09:00:00,`50302108032024011211111111111,`50302108032024011211111111111,`退款,`退款,`支出,`2.00,`110.34,`system,`,`
+资金流水总笔数,收入笔数,收入金额,支出笔数,支出金额
+`2,`1,`12.34,`1,`2.00
+";
+
+    #[test]
+    fn test_parse_cents() {
+        assert_eq!(parse_cents("0.01"), Ok(1));
+        assert_eq!(parse_cents("12.3"), Ok(1230));
+        assert_eq!(parse_cents("100"), Ok(10000));
+        assert_eq!(parse_cents("0.06500"), Ok(7));
+        assert_eq!(parse_cents("-0.01000"), Ok(-1));
+        assert!(parse_cents("1.2a").is_err());
+        assert!(parse_cents("").is_err());
+    }
+
+    #[test]
+    fn test_trade_bill() {
+        let mut reader = TradeBillReader::new(TRADE_BILL.as_bytes());
+        let records: Vec<TradeBillRecord> = reader
+            .by_ref()
+            .collect::<Result<_, _>>()
+            .expect("parse trade bill");
+        assert_eq!(records.len(), 2);
+
+        let payment = &records[0];
+        assert_eq!(payment.trade_time.to_rfc3339(), "2024-01-12T10:36:13+08:00");
+        assert_eq!(payment.settlement_total, 1234);
+        assert_eq!(payment.goods_name.as_deref(), Some("T恤,尺码L"));
+        assert_eq!(payment.fee, 7);
+        assert_eq!(payment.sub_mchid, None);
+        assert_eq!(payment.device_id, None);
+        assert!(!payment.is_refund());
+
+        let refund = &records[1];
+        assert!(refund.is_refund());
+        assert_eq!(refund.out_refund_no.as_deref(), Some("R20240112001"));
+        assert_eq!(refund.refund_amount, Some(200));
+        assert_eq!(refund.fee, -1);
+
+        let summary = reader.summary().expect("summary");
+        assert_eq!(summary.total_count, 2);
+        assert_eq!(summary.settlement_total, Some(1234));
+        assert_eq!(summary.refund_total, Some(200));
+        assert_eq!(summary.income_count, None);
+    }
+
+    #[test]
+    fn test_fund_flow_bill() {
+        let mut reader = FundFlowBillReader::new(FUND_FLOW_BILL.as_bytes());
+        let records = reader
+            .by_ref()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("parse fund flow bill");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].direction, FundDirection::Income);
+        assert_eq!(records[0].balance, 11234);
+        assert_eq!(records[1].direction, FundDirection::Expense);
+        assert_eq!(records[1].amount, 200);
+        assert_eq!(records[1].remark, None);
+
+        let summary = reader.summary().expect("summary");
+        assert_eq!(summary.total_count, 2);
+        assert_eq!(summary.income_amount, Some(1234));
+        assert_eq!(summary.expense_count, Some(1));
+    }
+
+    #[test]
+    fn test_bill_error_line() {
+        let bill = "交易时间,公众账号ID\n`not a time,`wx\n";
+        let error = TradeBillReader::new(bill.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_into_summary() {
+        let summary = TradeBillReader::new(TRADE_BILL.as_bytes())
+            .into_summary()
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.order_total, Some(1234));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::applyment::{
+    ApplymentParams, ApplymentResponse, ApplymentStateResponse, ModifySettlementParams,
+    ModifySettlementResponse, SettlementAccount,
+};
+use crate::error::PayError;
+use crate::pay::WechatPay;
+use crate::request::HttpMethod;
+use crate::sign::PlatformPublicKey;
+
+impl WechatPay {
+    /// 提交特约商户进件申请单，敏感字段使用`platform_key`加密
+    pub fn submit_applyment(
+        &self,
+        params: ApplymentParams,
+        platform_key: &PlatformPublicKey,
+    ) -> Result<ApplymentResponse, PayError> {
+        let url = "/v3/applyment4sub/applyment/";
+        let params = params.encrypt(platform_key)?;
+        self.pay_with_serial(HttpMethod::POST, url, params, platform_key.serial_no())
+    }
+
+    /// 通过业务申请编号查询申请单状态
+    pub fn query_applyment_by_business_code(
+        &self,
+        business_code: impl AsRef<str>,
+    ) -> Result<ApplymentStateResponse, PayError> {
+        let url = format!(
+            "/v3/applyment4sub/applyment/business_code/{}",
+            business_code.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 通过微信支付申请单号查询申请单状态
+    pub fn query_applyment_by_id(
+        &self,
+        applyment_id: u64,
+    ) -> Result<ApplymentStateResponse, PayError> {
+        let url = format!("/v3/applyment4sub/applyment/applyment_id/{}", applyment_id);
+        self.get_pay(url.as_str())
+    }
+
+    /// 修改特约商户结算账户，开户名称和银行账号使用`platform_key`加密
+    pub fn modify_settlement(
+        &self,
+        sub_mchid: impl AsRef<str>,
+        params: ModifySettlementParams,
+        platform_key: &PlatformPublicKey,
+    ) -> Result<ModifySettlementResponse, PayError> {
+        let url = format!(
+            "/v3/apply4sub/sub_merchants/{}/modify-settlement",
+            sub_mchid.as_ref()
+        );
+        let params = params.encrypt(platform_key)?;
+        self.pay_with_serial(
+            HttpMethod::POST,
+            url.as_str(),
+            params,
+            platform_key.serial_no(),
+        )
+    }
+
+    /// 查询特约商户结算账户
+    pub fn query_settlement(
+        &self,
+        sub_mchid: impl AsRef<str>,
+    ) -> Result<SettlementAccount, PayError> {
+        let url = format!(
+            "/v3/apply4sub/sub_merchants/{}/settlement",
+            sub_mchid.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::busifavor::{
+    AssociateBusifavorCouponParams, AssociateBusifavorCouponResponse, BusifavorStockDetail,
+    CreateBusifavorStockParams, CreateBusifavorStockResponse, ModifyBusifavorStockParams,
+    ReturnBusifavorCouponParams, ReturnBusifavorCouponResponse, UploadCouponCodesParams,
+    UploadCouponCodesResponse, UseBusifavorCouponParams, UseBusifavorCouponResponse,
+};
+use crate::error::PayError;
+use crate::pay::WechatPay;
+use crate::request::HttpMethod;
+
+impl WechatPay {
+    /// 创建商家券
+    pub fn create_busifavor_stock(
+        &self,
+        params: CreateBusifavorStockParams,
+    ) -> Result<CreateBusifavorStockResponse, PayError> {
+        let url = "/v3/marketing/busifavor/stocks";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 修改商家券基本信息
+    pub fn modify_busifavor_stock(
+        &self,
+        stock_id: impl AsRef<str>,
+        params: ModifyBusifavorStockParams,
+    ) -> Result<(), PayError> {
+        let url = format!("/v3/marketing/busifavor/stocks/{}", stock_id.as_ref());
+        self.pay_no_content(HttpMethod::PATCH, url.as_str(), params)
+    }
+
+    /// 查询商家券批次详情
+    pub fn query_busifavor_stock(
+        &self,
+        stock_id: impl AsRef<str>,
+    ) -> Result<BusifavorStockDetail, PayError> {
+        let url = format!("/v3/marketing/busifavor/stocks/{}", stock_id.as_ref());
+        self.get_pay(url.as_str())
+    }
+
+    /// 上传预存code
+    pub fn upload_busifavor_coupon_codes(
+        &self,
+        stock_id: impl AsRef<str>,
+        params: UploadCouponCodesParams,
+    ) -> Result<UploadCouponCodesResponse, PayError> {
+        let url = format!(
+            "/v3/marketing/busifavor/stocks/{}/couponcodes",
+            stock_id.as_ref()
+        );
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 核销用户券
+    pub fn use_busifavor_coupon(
+        &self,
+        params: UseBusifavorCouponParams,
+    ) -> Result<UseBusifavorCouponResponse, PayError> {
+        let url = "/v3/marketing/busifavor/coupons/use";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 申请退券
+    pub fn return_busifavor_coupon(
+        &self,
+        params: ReturnBusifavorCouponParams,
+    ) -> Result<ReturnBusifavorCouponResponse, PayError> {
+        let url = "/v3/marketing/busifavor/coupons/return";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 关联订单信息
+    pub fn associate_busifavor_coupon(
+        &self,
+        params: AssociateBusifavorCouponParams,
+    ) -> Result<AssociateBusifavorCouponResponse, PayError> {
+        let url = "/v3/marketing/busifavor/coupons/associate";
+        self.pay(HttpMethod::POST, url, params)
+    }
+}
@@ -0,0 +1,244 @@
+use crate::ecommerce::{
+    AccountType, CloseCombineOrderParams, CombineOrder, CombinePayParams, CombinePayResponse,
+    CombinePayType, EcommerceApplymentParams, EcommerceApplymentResponse,
+    EcommerceApplymentStateResponse, EcommerceBillResponse, EcommerceProfitSharingOrder,
+    EcommerceProfitSharingParams, EcommerceRefund, EcommerceRefundParams,
+    FinishProfitSharingParams, FundBalance, ProfitSharingReturnParams, ProfitSharingReturnResponse,
+    Withdraw, WithdrawParams,
+};
+use crate::error::{PayError, ValidationError};
+use crate::pay::WechatPay;
+use crate::request::HttpMethod;
+use crate::sign::PlatformPublicKey;
+
+impl WechatPay {
+    /// 提交二级商户进件申请，敏感字段使用`platform_key`加密
+    pub fn submit_ecommerce_applyment(
+        &self,
+        params: EcommerceApplymentParams,
+        platform_key: &PlatformPublicKey,
+    ) -> Result<EcommerceApplymentResponse, PayError> {
+        let url = "/v3/ecommerce/applyments/";
+        let params = params.encrypt(platform_key)?;
+        self.pay_with_serial(HttpMethod::POST, url, params, platform_key.serial_no())
+    }
+
+    /// 通过微信支付申请单号查询二级商户进件状态
+    pub fn query_ecommerce_applyment(
+        &self,
+        applyment_id: u64,
+    ) -> Result<EcommerceApplymentStateResponse, PayError> {
+        let url = format!("/v3/ecommerce/applyments/{}", applyment_id);
+        self.get_pay(url.as_str())
+    }
+
+    /// 通过业务申请编号查询二级商户进件状态
+    pub fn query_ecommerce_applyment_by_out_request_no(
+        &self,
+        out_request_no: impl AsRef<str>,
+    ) -> Result<EcommerceApplymentStateResponse, PayError> {
+        let url = format!(
+            "/v3/ecommerce/applyments/out-request-no/{}",
+            out_request_no.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 合单下单，每个子单指定二级商户号
+    pub fn combine_pay(
+        &self,
+        pay_type: CombinePayType,
+        params: CombinePayParams,
+    ) -> Result<CombinePayResponse, PayError> {
+        let url = format!("/v3/combine-transactions/{}", pay_type.path());
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 合单查询
+    pub fn query_combine_order(
+        &self,
+        combine_out_trade_no: impl AsRef<str>,
+    ) -> Result<CombineOrder, PayError> {
+        let url = format!(
+            "/v3/combine-transactions/out-trade-no/{}",
+            combine_out_trade_no.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 合单关单
+    pub fn close_combine_order(
+        &self,
+        combine_out_trade_no: impl AsRef<str>,
+        params: CloseCombineOrderParams,
+    ) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/combine-transactions/out-trade-no/{}/close",
+            combine_out_trade_no.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 请求分账，接收方包含个人姓名时需要传入`platform_key`加密
+    pub fn ecommerce_profit_sharing(
+        &self,
+        params: EcommerceProfitSharingParams,
+        platform_key: Option<&PlatformPublicKey>,
+    ) -> Result<EcommerceProfitSharingOrder, PayError> {
+        let url = "/v3/ecommerce/profitsharing/orders";
+        match platform_key {
+            Some(key) if params.has_receiver_name() => {
+                let params = params.encrypt(key)?;
+                self.pay_with_serial(HttpMethod::POST, url, params, key.serial_no())
+            }
+            None if params.has_receiver_name() => {
+                Err(PayError::Validation(vec![ValidationError::new(
+                    "receiver_name",
+                    "requires platform key to encrypt",
+                )]))
+            }
+            _ => self.pay(HttpMethod::POST, url, params),
+        }
+    }
+
+    /// 查询分账结果
+    pub fn query_ecommerce_profit_sharing(
+        &self,
+        sub_mchid: impl AsRef<str>,
+        transaction_id: impl AsRef<str>,
+        out_order_no: impl AsRef<str>,
+    ) -> Result<EcommerceProfitSharingOrder, PayError> {
+        let url = format!(
+            "/v3/ecommerce/profitsharing/orders?sub_mchid={}&transaction_id={}&out_order_no={}",
+            sub_mchid.as_ref(),
+            transaction_id.as_ref(),
+            out_order_no.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 完结分账
+    pub fn finish_ecommerce_profit_sharing(
+        &self,
+        params: FinishProfitSharingParams,
+    ) -> Result<EcommerceProfitSharingOrder, PayError> {
+        let url = "/v3/ecommerce/profitsharing/finish-order";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 请求分账回退
+    pub fn ecommerce_profit_sharing_return(
+        &self,
+        params: ProfitSharingReturnParams,
+    ) -> Result<ProfitSharingReturnResponse, PayError> {
+        let url = "/v3/ecommerce/profitsharing/returnorders";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 二级商户订单申请退款
+    pub fn ecommerce_refund(
+        &self,
+        params: EcommerceRefundParams,
+    ) -> Result<EcommerceRefund, PayError> {
+        let url = "/v3/ecommerce/refunds/apply";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 通过商户退款单号查询退款
+    pub fn query_ecommerce_refund(
+        &self,
+        sub_mchid: impl AsRef<str>,
+        out_refund_no: impl AsRef<str>,
+    ) -> Result<EcommerceRefund, PayError> {
+        let url = format!(
+            "/v3/ecommerce/refunds/out-refund-no/{}?sub_mchid={}",
+            out_refund_no.as_ref(),
+            sub_mchid.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 查询二级商户账户实时余额
+    pub fn query_sub_merchant_balance(
+        &self,
+        sub_mchid: impl AsRef<str>,
+        account_type: AccountType,
+    ) -> Result<FundBalance, PayError> {
+        let url = format!(
+            "/v3/ecommerce/fund/balance/{}?account_type={}",
+            sub_mchid.as_ref(),
+            account_type.as_str()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 查询电商平台账户实时余额
+    pub fn query_platform_balance(
+        &self,
+        account_type: AccountType,
+    ) -> Result<FundBalance, PayError> {
+        let url = format!("/v3/merchant/fund/balance/{}", account_type.as_str());
+        self.get_pay(url.as_str())
+    }
+
+    /// 二级商户余额提现
+    pub fn ecommerce_withdraw(&self, params: WithdrawParams) -> Result<Withdraw, PayError> {
+        let url = "/v3/ecommerce/fund/withdraw";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 通过微信支付提现单号查询二级商户提现状态
+    pub fn query_ecommerce_withdraw(
+        &self,
+        sub_mchid: impl AsRef<str>,
+        withdraw_id: impl AsRef<str>,
+    ) -> Result<Withdraw, PayError> {
+        let url = format!(
+            "/v3/ecommerce/fund/withdraw/{}?sub_mchid={}",
+            withdraw_id.as_ref(),
+            sub_mchid.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 通过商户提现单号查询二级商户提现状态
+    pub fn query_ecommerce_withdraw_by_out_request_no(
+        &self,
+        sub_mchid: impl AsRef<str>,
+        out_request_no: impl AsRef<str>,
+    ) -> Result<Withdraw, PayError> {
+        let url = format!(
+            "/v3/ecommerce/fund/withdraw/out-request-no/{}?sub_mchid={}",
+            out_request_no.as_ref(),
+            sub_mchid.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 下载二级商户资金账单，`bill_date`格式为yyyy-MM-dd，返回解密后的各个账单文件
+    pub fn download_ecommerce_fundflow_bill(
+        &self,
+        bill_date: impl AsRef<str>,
+        account_type: AccountType,
+    ) -> Result<Vec<Vec<u8>>, PayError> {
+        let url = format!(
+            "/v3/ecommerce/bill/fundflowbill?bill_date={}&account_type={}&algorithm=AEAD_AES_256_GCM",
+            bill_date.as_ref(),
+            account_type.as_str()
+        );
+        let response: EcommerceBillResponse = self.get_pay(url.as_str())?;
+        if let Some(code) = &response.code {
+            return Err(PayError::BillError(format!(
+                "{}: {}",
+                code,
+                response.message.clone().unwrap_or_default()
+            )));
+        }
+        let mut bills = Vec::with_capacity(response.download_bill_list.len());
+        for bill in response.download_bill_list.iter() {
+            let content = self.download(bill.download_url.as_str())?;
+            bills.push(bill.decrypt(self, &content)?);
+        }
+        Ok(bills)
+    }
+}
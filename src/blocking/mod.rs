@@ -1 +1,11 @@
+pub mod applyment;
+pub mod busifavor;
+pub mod complaint;
+pub mod ecommerce;
+pub mod favor;
+pub mod media;
+pub mod papay;
 pub mod pay;
+pub mod payscore;
+pub mod registry;
+pub mod v2;
@@ -0,0 +1,65 @@
+use crate::error::PayError;
+use crate::papay::{
+    Contract, PapayTransactionParams, PreEntrustSignParams, PreEntrustSignResponse,
+    TerminateContractParams,
+};
+use crate::pay::{WechatPay, WechatPayTrait};
+use crate::request::HttpMethod;
+
+impl WechatPay {
+    /// 委托代扣预签约，APP、JSAPI、小程序返回预签约ID，H5返回签约跳转链接
+    pub fn pre_entrust_sign(
+        &self,
+        params: PreEntrustSignParams,
+    ) -> Result<PreEntrustSignResponse, PayError> {
+        let url = format!(
+            "/v3/papay/sign/contracts/pre-entrust-sign/{}",
+            params.sign_type.path()
+        );
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 通过委托代扣协议ID查询签约协议
+    pub fn query_contract(&self, contract_id: impl AsRef<str>) -> Result<Contract, PayError> {
+        let url = format!(
+            "/v3/papay/sign/contracts/contract-id/{}?appid={}",
+            contract_id.as_ref(),
+            self.appid()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 通过模板ID和商户签约协议号查询签约协议
+    pub fn query_contract_by_out_contract_code(
+        &self,
+        plan_id: u64,
+        out_contract_code: impl AsRef<str>,
+    ) -> Result<Contract, PayError> {
+        let url = format!(
+            "/v3/papay/sign/contracts/plan-id/{}/out-contract-code/{}?appid={}",
+            plan_id,
+            out_contract_code.as_ref(),
+            self.appid()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 通过委托代扣协议ID解约
+    pub fn terminate_contract(
+        &self,
+        contract_id: impl AsRef<str>,
+        params: TerminateContractParams,
+    ) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/papay/sign/contracts/contract-id/{}/terminate",
+            contract_id.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 申请扣款，受理成功返回后扣款结果以支付通知或查询订单为准
+    pub fn apply_papay_transaction(&self, params: PapayTransactionParams) -> Result<(), PayError> {
+        let url = "/v3/papay/pay/transactions/apply";
+        self.pay_no_content(HttpMethod::POST, url, params)
+    }
+}
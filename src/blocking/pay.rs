@@ -1,34 +1,50 @@
+use crate::debug;
 use crate::error::PayError;
-use crate::model::{AppParams, H5Params, JsapiParams, MicroParams, NativeParams, ParamsTrait};
+use crate::model::{
+    AppParams, CloseOrderParams, CodepayParams, H5Params, JsapiParams, MicroParams, NativeParams,
+    ParamsTrait, ReverseParams,
+};
 use crate::pay::{WechatPay, WechatPayTrait};
+use crate::pay_type::TradeState;
+use crate::poll::{self, Backoff};
 use crate::request::HttpMethod;
 use crate::response::{
-    AppResponse, CertificateResponse, H5Response, JsapiResponse, MicroResponse, NativeResponse,
-    ResponseTrait, SignData,
+    AppResponse, CertificateResponse, CodepayResponse, H5Response, JsapiResponse, MicroResponse,
+    NativeResponse, QueryOrderResponse, ResponseTrait,
 };
-use reqwest::header::{HeaderMap, REFERER};
-use serde::Deserialize;
-use serde_json::{Map, Value};
-use crate::{debug};
+use reqwest::header::{HeaderValue, LOCATION, REFERER};
+use std::time::{Duration, Instant};
+
+/// 打开h5_url时最多跟随的跳转次数
+const MAX_REDIRECTS: usize = 10;
 
 impl WechatPay {
-    pub fn pay<P: ParamsTrait, R: ResponseTrait>(
+    fn send<P: ParamsTrait>(
         &self,
         method: HttpMethod,
         url: &str,
-        json: P,
-    ) -> Result<R, PayError> {
-        let json_str = json.to_json();
-        debug!("json_str: {}", json_str);
-        let mut map: Map<String, Value> = serde_json::from_str(&json_str)?;
-        map.insert("appid".to_owned(), self.appid().into());
-        map.insert("mchid".to_owned(), self.mch_id().into());
-        map.insert("notify_url".to_owned(), self.notify_url().into());
-        let body = serde_json::to_string(&map)?;
-        let headers = self.build_header(method.clone(), url, body.as_str())?;
-        let client = reqwest::blocking::Client::new();
+        mut json: P,
+        serial_no: Option<&str>,
+    ) -> Result<reqwest::blocking::Response, PayError> {
+        json.fill_defaults(self);
+        json.validate()?;
+        let body = json.to_json();
+        debug!("json_str: {}", crate::secret::mask_json(&body));
+        let mut headers = self.build_header(method.clone(), url, body.as_str())?;
+        if let Some(serial_no) = serial_no {
+            headers.insert(
+                "Wechatpay-Serial",
+                HeaderValue::from_str(serial_no)
+                    .map_err(|e| PayError::CertificateError(e.to_string()))?,
+            );
+        }
+        let client = self.client();
         let url = format!("{}{}", self.base_url(), url);
-        debug!("url: {} body: {}", url, body);
+        debug!(
+            "url: {} body: {}",
+            crate::secret::mask_url(&url),
+            crate::secret::mask_json(&body)
+        );
         let builder = match method {
             HttpMethod::GET => client.get(url),
             HttpMethod::POST => client.post(url),
@@ -36,21 +52,53 @@ impl WechatPay {
             HttpMethod::DELETE => client.delete(url),
             HttpMethod::PATCH => client.patch(url),
         };
+        Ok(builder.headers(headers).body(body).send()?)
+    }
 
-        builder
-            .headers(headers)
-            .body(body)
-            .send()?
+    pub fn pay<P: ParamsTrait, R: ResponseTrait>(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        json: P,
+    ) -> Result<R, PayError> {
+        self.send(method, url, json, None)?.json::<R>().map(Ok)?
+    }
+
+    /// 请求中包含使用平台证书加密的敏感信息时，请求头需要带上加密所用的平台证书序列号
+    pub fn pay_with_serial<P: ParamsTrait, R: ResponseTrait>(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        json: P,
+        serial_no: &str,
+    ) -> Result<R, PayError> {
+        self.send(method, url, json, Some(serial_no))?
             .json::<R>()
             .map(Ok)?
     }
 
+    /// 发起无响应内容的请求，例如关闭订单，成功时返回204
+    pub fn pay_no_content<P: ParamsTrait>(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        json: P,
+    ) -> Result<(), PayError> {
+        let response = self.send(method, url, json, None)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text()?;
+        Err(crate::pay::wechat_error(status.as_u16(), &text))
+    }
+
     pub fn get_pay<R: ResponseTrait>(&self, url: &str) -> Result<R, PayError> {
         let body = "";
         let headers = self.build_header(HttpMethod::GET, url, body)?;
-        let client = reqwest::blocking::Client::new();
+        let client = self.client();
         let url = format!("{}{}", self.base_url(), url);
-        debug!("url: {} body: {}", url, body);
+        debug!("url: {} body: {}", crate::secret::mask_url(&url), body);
         client
             .get(url)
             .headers(headers)
@@ -60,6 +108,35 @@ impl WechatPay {
             .map(Ok)?
     }
 
+    /// 发起无请求主体的DELETE请求，成功时返回204
+    pub fn delete_pay(&self, url: &str) -> Result<(), PayError> {
+        let body = "";
+        let headers = self.build_header(HttpMethod::DELETE, url, body)?;
+        let url = format!("{}{}", self.base_url(), url);
+        debug!("url: {} body: {}", crate::secret::mask_url(&url), body);
+        let response = self.client().delete(url).headers(headers).send()?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text()?;
+        Err(crate::pay::wechat_error(status.as_u16(), &text))
+    }
+
+    /// 下载账单、明细等文件，`download_url`为接口返回的完整下载地址
+    pub fn download(&self, download_url: &str) -> Result<Vec<u8>, PayError> {
+        let url = crate::pay::url_path(download_url);
+        let headers = self.build_header(HttpMethod::GET, url, "")?;
+        debug!("download url: {}", crate::secret::mask_url(download_url));
+        let response = self.client().get(download_url).headers(headers).send()?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text()?;
+            return Err(crate::pay::wechat_error(status.as_u16(), &text));
+        }
+        Ok(response.bytes()?.to_vec())
+    }
+
     pub fn h5_pay(&self, params: H5Params) -> Result<H5Response, PayError> {
         let url = "/v3/pay/transactions/h5";
         self.pay(HttpMethod::POST, url, params)
@@ -80,6 +157,7 @@ impl WechatPay {
             })
     }
 
+    /// 小程序支付，使用JSAPI下单接口。付款码支付请使用`codepay`
     pub fn micro_pay(&self, params: MicroParams) -> Result<MicroResponse, PayError> {
         let url = "/v3/pay/transactions/jsapi";
         self.pay(HttpMethod::POST, url, params)
@@ -115,24 +193,219 @@ impl WechatPay {
                 result
             })
     }
-    pub fn get_weixin<S>(&self, h5_url: S, referer: S) -> Result<Option<String>, PayError>
-        where
-            S: AsRef<str>,
+    /// 打开H5支付返回的`h5_url`，提取拉起微信客户端的`weixin://`链接。
+    /// `referer`需要是H5支付的授权域名，跟随跳转时每次都带上该referer
+    /// 请求使用默认设置的共享客户端，不使用`with_client`设置的代理、超时等
+    pub fn get_weixin<S>(&self, h5_url: S, referer: S) -> Result<String, PayError>
+    where
+        S: AsRef<str>,
     {
-        let client = reqwest::blocking::Client::new();
-        let mut headers = HeaderMap::new();
-        headers.insert(REFERER, referer.as_ref().parse().unwrap());
-        let body = client.get(h5_url.as_ref()).headers(headers).send()?;
-        let text = body.text()?;
-        text.split("\n")
-            .find(|line| line.contains("weixin://"))
-            .map(|line| {
-                line.split(r#"""#)
-                    .find(|line| line.contains("weixin://"))
-                    .map(|line| line.to_string())
-            })
-            .ok_or_else(|| PayError::WeixinNotFound)
+        let referer = HeaderValue::from_str(referer.as_ref())
+            .map_err(|e| PayError::WechatError(format!("invalid referer: {}", e)))?;
+        let mut url = reqwest::Url::parse(h5_url.as_ref())
+            .map_err(|e| PayError::WechatError(format!("invalid h5_url: {}", e)))?;
+        for _ in 0..=MAX_REDIRECTS {
+            let response = crate::pay::no_redirect_client()?
+                .get(url.clone())
+                .header(REFERER, referer.clone())
+                .send()?;
+            let status = response.status();
+            if status.is_redirection() {
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|location| location.to_str().ok())
+                    .ok_or(PayError::WeixinNotFound)?;
+                if location.starts_with("weixin://") {
+                    return Ok(location.to_string());
+                }
+                url = url
+                    .join(location)
+                    .map_err(|e| PayError::WechatError(format!("invalid redirect: {}", e)))?;
+                continue;
+            }
+            let text = response.text()?;
+            if !status.is_success() {
+                return match crate::h5::page_error(&text) {
+                    PayError::WeixinNotFound => Err(PayError::WechatError(format!(
+                        "h5_url status {}",
+                        status.as_u16()
+                    ))),
+                    error => Err(error),
+                };
+            }
+            return crate::h5::extract_weixin_url(&text);
+        }
+        Err(PayError::WechatError(format!(
+            "h5_url redirected more than {} times",
+            MAX_REDIRECTS
+        )))
+    }
+    /// 微信支付订单号查询订单
+    pub fn query_order_by_transaction_id(
+        &self,
+        transaction_id: impl AsRef<str>,
+    ) -> Result<QueryOrderResponse, PayError> {
+        let url = format!(
+            "/v3/pay/transactions/id/{}?mchid={}",
+            transaction_id.as_ref(),
+            self.mch_id()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 商户订单号查询订单
+    pub fn query_order_by_out_trade_no(
+        &self,
+        out_trade_no: impl AsRef<str>,
+    ) -> Result<QueryOrderResponse, PayError> {
+        let url = format!(
+            "/v3/pay/transactions/out-trade-no/{}?mchid={}",
+            out_trade_no.as_ref(),
+            self.mch_id()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 关闭订单，关闭后用户不能再支付
+    pub fn close_order(&self, out_trade_no: impl AsRef<str>) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/pay/transactions/out-trade-no/{}/close",
+            out_trade_no.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), CloseOrderParams::default())
+    }
+
+    /// 轮询订单直到支付成功、已关闭等终态或超时，返回最后查询到的交易状态。
+    /// 查询间隔从`interval`开始逐渐增加，超时时返回的状态为未支付或用户支付中。
+    /// 网络错误、订单尚未生成等可重试的错误会继续查询，超时时最后一次查询仍失败则返回该错误
+    pub fn wait_for_payment(
+        &self,
+        out_trade_no: impl AsRef<str>,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<TradeState, PayError> {
+        let out_trade_no = out_trade_no.as_ref();
+        let mut backoff = Backoff::new(timeout, interval);
+        loop {
+            let last = match self.query_order_by_out_trade_no(out_trade_no) {
+                Ok(response) if response.is_retryable() => response.into_trade_state(),
+                Ok(response) => match response.into_trade_state()? {
+                    state if state.is_terminal() => return Ok(state),
+                    state => Ok(state),
+                },
+                Err(e) if poll::is_transient(&e) => Err(e),
+                Err(e) => return Err(e),
+            };
+            match backoff.next_delay() {
+                Some(delay) => std::thread::sleep(delay),
+                None => return last,
+            }
+        }
+    }
+
+    /// 与`wait_for_payment`相同，超时后关闭订单。
+    /// 关闭失败时（例如用户恰好完成支付）返回订单的最新状态
+    pub fn wait_for_payment_or_close(
+        &self,
+        out_trade_no: impl AsRef<str>,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<TradeState, PayError> {
+        let out_trade_no = out_trade_no.as_ref();
+        let state = self.wait_for_payment(out_trade_no, timeout, interval)?;
+        if state.is_terminal() {
+            return Ok(state);
+        }
+        match self.close_order(out_trade_no) {
+            Ok(()) => Ok(TradeState::Closed),
+            Err(PayError::WechatError(_)) => self
+                .query_order_by_out_trade_no(out_trade_no)?
+                .into_trade_state(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 付款码支付，商户扫描用户的付款码收款。小程序支付请使用`micro_pay`
+    pub fn codepay(&self, params: CodepayParams) -> Result<CodepayResponse, PayError> {
+        let url = "/v3/pay/transactions/codepay";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 撤销付款码订单，用户已支付的金额会原路退回
+    pub fn reverse_order(&self, out_trade_no: impl AsRef<str>) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/pay/transactions/out-trade-no/{}/reverse",
+            out_trade_no.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), ReverseParams::default())
+    }
+
+    /// 付款码支付并确认支付结果。
+    /// 用户需要输入密码或结果未知时轮询订单，超时仍未支付成功则撤销订单并返回`TradeState::Revoked`，
+    /// `timeout`是整个调用的等待时间，轮询时为撤销重试预留时间，撤销只使用剩余的时间，但至少会撤销一次。
+    /// 系统错误、网络错误等可重试的撤销失败会重试，仍然失败则返回`PayError::ReverseFailed`，需要人工处理
+    pub fn codepay_or_reverse(
+        &self,
+        params: CodepayParams,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<TradeState, PayError> {
+        let started = Instant::now();
+        let out_trade_no = params.out_trade_no.clone();
+        match self.codepay(params) {
+            Ok(response) => {
+                if let Some(state) = response.codepay_trade_state()? {
+                    if state.is_terminal() {
+                        return Ok(state);
+                    }
+                }
+            }
+            Err(PayError::RequestError(_)) => {}
+            Err(e) => return Err(e),
+        }
+        // 查询失败时订单状态同样不确定，按微信支付的建议撤销订单
+        let wait_timeout = timeout
+            .saturating_sub(poll::reverse_reserve(interval))
+            .saturating_sub(started.elapsed());
+        if let Ok(state) = self.wait_for_payment(&out_trade_no, wait_timeout, interval) {
+            if state.is_terminal() {
+                return Ok(state);
+            }
+        }
+        let remaining = timeout.saturating_sub(started.elapsed());
+        self.reverse_with_retry(out_trade_no, remaining, interval)
+    }
+
+    /// 撤销订单，可重试的错误在`timeout`内按退避间隔重试，其他错误或多次失败后返回`PayError::ReverseFailed`
+    fn reverse_with_retry(
+        &self,
+        out_trade_no: String,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<TradeState, PayError> {
+        let mut backoff = Backoff::new(timeout, interval);
+        let mut attempts = 0;
+        loop {
+            let err = match self.reverse_order(&out_trade_no) {
+                Ok(()) => return Ok(TradeState::Revoked),
+                Err(e) => e,
+            };
+            attempts += 1;
+            match backoff.next_delay() {
+                Some(delay) if attempts < poll::REVERSE_ATTEMPTS && poll::is_transient(&err) => {
+                    std::thread::sleep(delay)
+                }
+                _ => {
+                    return Err(PayError::ReverseFailed {
+                        out_trade_no,
+                        source: Box::new(err),
+                    })
+                }
+            }
+        }
     }
+
     pub fn certificates(&self) -> Result<CertificateResponse, PayError> {
         let url = "/v3/certificates";
         self.get_pay(url)
@@ -143,7 +416,7 @@ impl WechatPay {
 mod tests {
     use std::io::Write;
     use crate::model::{
-        AppParams, H5Params, H5SceneInfo, JsapiParams, MicroParams, NativeParams, SceneInfo,
+        AppParams, H5Params, H5SceneInfo, JsapiParams, MicroParams, NativeParams,
     };
     use crate::pay::{PayNotifyTrait, WechatPay};
     use crate::response::Certificate;
@@ -217,6 +490,7 @@ mod tests {
     pub fn test_str() {
         let str = r#" deeplink : "weixin://wap/pay?prepayid%3Dwx122129234529163c948432e26bc0030000&package=4206921243&noncestr=1705066163&sign=788bc4a9f8f44c6f708aff38c4b48a85""#;
         let strs = str.split(r#"""#).find(|line| line.contains("weixin://"));
+        assert!(strs.unwrap().starts_with("weixin://wap/pay?"));
     }
 
     #[test]
@@ -235,7 +509,7 @@ mod tests {
         let weixin_url = wechat_pay
             .get_weixin(body.h5_url.unwrap().as_str(), "https://mydomain.com")
             .unwrap();
-        debug!("weixin_url: {}", weixin_url.unwrap());
+        debug!("weixin_url: {}", weixin_url);
     }
 
     #[test]
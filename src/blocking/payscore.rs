@@ -0,0 +1,138 @@
+use crate::error::PayError;
+use crate::pay::{WechatPay, WechatPayTrait};
+use crate::payscore::{
+    CancelServiceOrderParams, CreateServiceOrderParams, ModifyServiceOrderParams,
+    PayScorePermissionParams, PayScorePermissionResponse, ServiceOrder, ServiceOrderActionParams,
+    ServiceOrderAmountParams, SyncServiceOrderParams, TerminatePermissionParams, UserServiceState,
+};
+use crate::request::HttpMethod;
+
+impl WechatPay {
+    /// 创建支付分订单
+    pub fn create_service_order(
+        &self,
+        params: CreateServiceOrderParams,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = "/v3/payscore/serviceorder";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 查询支付分订单
+    pub fn query_service_order(
+        &self,
+        service_id: impl AsRef<str>,
+        out_order_no: impl AsRef<str>,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = format!(
+            "/v3/payscore/serviceorder?service_id={}&out_order_no={}&appid={}",
+            service_id.as_ref(),
+            out_order_no.as_ref(),
+            self.appid()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 取消支付分订单
+    pub fn cancel_service_order(
+        &self,
+        out_order_no: impl AsRef<str>,
+        params: CancelServiceOrderParams,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = format!("/v3/payscore/serviceorder/{}/cancel", out_order_no.as_ref());
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 修改支付分订单金额
+    pub fn modify_service_order(
+        &self,
+        out_order_no: impl AsRef<str>,
+        params: ModifyServiceOrderParams,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = format!("/v3/payscore/serviceorder/{}/modify", out_order_no.as_ref());
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 完结支付分订单
+    pub fn complete_service_order(
+        &self,
+        out_order_no: impl AsRef<str>,
+        params: ServiceOrderAmountParams,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = format!(
+            "/v3/payscore/serviceorder/{}/complete",
+            out_order_no.as_ref()
+        );
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 商户发起催收扣款
+    pub fn collect_service_order(
+        &self,
+        out_order_no: impl AsRef<str>,
+        params: ServiceOrderActionParams,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = format!("/v3/payscore/serviceorder/{}/pay", out_order_no.as_ref());
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 同步支付分订单信息，用户通过其他方式付款时调用
+    pub fn sync_service_order(
+        &self,
+        out_order_no: impl AsRef<str>,
+        params: SyncServiceOrderParams,
+    ) -> Result<ServiceOrder, PayError> {
+        let url = format!("/v3/payscore/serviceorder/{}/sync", out_order_no.as_ref());
+        self.pay(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 商户预授权，返回跳转授权页面的token
+    pub fn apply_payscore_permission(
+        &self,
+        params: PayScorePermissionParams,
+    ) -> Result<PayScorePermissionResponse, PayError> {
+        let url = "/v3/payscore/permissions";
+        self.pay(HttpMethod::POST, url, params)
+    }
+
+    /// 通过授权协议号查询用户授权记录
+    pub fn query_payscore_permission(
+        &self,
+        service_id: impl AsRef<str>,
+        authorization_code: impl AsRef<str>,
+    ) -> Result<UserServiceState, PayError> {
+        let url = format!(
+            "/v3/payscore/permissions/authorization-code/{}?service_id={}",
+            authorization_code.as_ref(),
+            service_id.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+
+    /// 通过授权协议号解除用户授权关系
+    pub fn terminate_payscore_permission(
+        &self,
+        authorization_code: impl AsRef<str>,
+        params: TerminatePermissionParams,
+    ) -> Result<(), PayError> {
+        let url = format!(
+            "/v3/payscore/permissions/authorization-code/{}/terminate",
+            authorization_code.as_ref()
+        );
+        self.pay_no_content(HttpMethod::POST, url.as_str(), params)
+    }
+
+    /// 查询用户授权状态
+    pub fn query_user_service_state(
+        &self,
+        service_id: impl AsRef<str>,
+        openid: impl AsRef<str>,
+    ) -> Result<UserServiceState, PayError> {
+        let url = format!(
+            "/v3/payscore/user-service-state?service_id={}&appid={}&openid={}",
+            service_id.as_ref(),
+            self.appid(),
+            openid.as_ref()
+        );
+        self.get_pay(url.as_str())
+    }
+}
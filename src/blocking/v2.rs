@@ -0,0 +1,58 @@
+use crate::debug;
+use crate::error::PayError;
+use crate::v2::model::{
+    OrderQueryParams, OrderQueryResponse, SendRedPackParams, SendRedPackResponse,
+    UnifiedOrderParams, UnifiedOrderResponse,
+};
+use crate::v2::{SignType, V2ParamsTrait, WechatPayV2};
+use serde::de::DeserializeOwned;
+
+impl WechatPayV2 {
+    /// 发送XML请求，`verify_sign`为false时不校验响应签名，用于不返回签名的接口
+    pub fn post_xml<P: V2ParamsTrait, R: DeserializeOwned>(
+        &self,
+        url: &str,
+        params: P,
+        sign_type: SignType,
+        verify_sign: bool,
+    ) -> Result<R, PayError> {
+        let body = self.build_xml(params, sign_type)?;
+        let url = format!("{}{}", self.base_url, url);
+        debug!("url: {} body: {}", url, crate::secret::mask_xml(&body));
+        let text = self
+            .client()
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "text/xml; charset=utf-8")
+            .body(body)
+            .send()?
+            .text()?;
+        debug!("response: {}", crate::secret::mask_xml(&text));
+        self.parse_xml(&text, sign_type, verify_sign)
+    }
+
+    /// 统一下单
+    pub fn unified_order(
+        &self,
+        params: UnifiedOrderParams,
+    ) -> Result<UnifiedOrderResponse, PayError> {
+        self.post_xml("/pay/unifiedorder", params, self.sign_type, true)
+    }
+
+    /// 查询订单
+    pub fn order_query(&self, params: OrderQueryParams) -> Result<OrderQueryResponse, PayError> {
+        self.post_xml("/pay/orderquery", params, self.sign_type, true)
+    }
+
+    /// 发放现金红包，响应不包含签名
+    pub fn send_red_pack(
+        &self,
+        params: SendRedPackParams,
+    ) -> Result<SendRedPackResponse, PayError> {
+        self.post_xml(
+            "/mmpaymkttransfers/sendredpack",
+            params,
+            SignType::Md5,
+            false,
+        )
+    }
+}
@@ -0,0 +1,589 @@
+//! 商家券，批次创建、修改、查询，上传预存code，核销、退券、关联订单，小程序发券插件签名
+use crate::datetime;
+use crate::macros::params_builder;
+use crate::money::Money;
+use crate::pay::WechatPayTrait;
+use crate::response::ResponseTrait;
+use crate::v2::SignType;
+use crate::validation;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 商家券类型
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BusifavorStockType {
+    ///固定面额满减券
+    Normal,
+    ///折扣券
+    Discount,
+    ///换购券
+    Exchange,
+}
+
+/// 券code模式
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CouponCodeMode {
+    ///系统分配券code
+    WechatpayMode,
+    ///商户发放时接口指定券code
+    MerchantApi,
+    ///商户上传自定义code
+    MerchantUpload,
+}
+
+/// 券可核销时间
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CouponAvailableTime {
+    ///【开始时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub available_begin_time: DateTime<FixedOffset>,
+    ///【结束时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub available_end_time: DateTime<FixedOffset>,
+    ///【生效后N天内有效】 领券后N天内有效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_day_after_receive: Option<u32>,
+    ///【领取后N天开始生效】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_days_after_receive: Option<u32>,
+}
+
+/// 固定面额满减券使用规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BusifavorFixedNormalCoupon {
+    ///【优惠金额】 单位为分
+    pub discount_amount: Money,
+    ///【消费门槛】 单位为分
+    pub transaction_minimum: Money,
+}
+
+/// 折扣券使用规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiscountCoupon {
+    ///【折扣百分比】 例如88为八八折
+    pub discount_percent: u32,
+    ///【消费门槛】 单位为分
+    pub transaction_minimum: Money,
+}
+
+/// 换购券使用规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExchangeCoupon {
+    ///【单品换购价】 单位为分
+    pub exchange_price: Money,
+    ///【消费门槛】 单位为分
+    pub transaction_minimum: Money,
+}
+
+/// 核销规则，按券类型设置对应的使用规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BusifavorCouponUseRule {
+    ///【券可核销时间】
+    pub coupon_available_time: CouponAvailableTime,
+    ///【固定面额满减券使用规则】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_normal_coupon: Option<BusifavorFixedNormalCoupon>,
+    ///【折扣券使用规则】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_coupon: Option<DiscountCoupon>,
+    ///【换购券使用规则】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exchange_coupon: Option<ExchangeCoupon>,
+    ///【核销方式】 OFF_LINE、MINI_PROGRAMS、SELF_CONSUME、PAYMENT_CODE
+    pub use_method: String,
+    ///【小程序appid】 核销方式为MINI_PROGRAMS时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_programs_appid: Option<String>,
+    ///【小程序path】 核销方式为MINI_PROGRAMS时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_programs_path: Option<String>,
+}
+
+/// 发放规则
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BusifavorStockSendRule {
+    ///【批次总预算】 减至券和折扣券不填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<Money>,
+    ///【批次最大发放个数】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_coupons: Option<u64>,
+    ///【用户最大可领个数】
+    pub max_coupons_per_user: u32,
+    ///【单天发放上限个数】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_coupons_by_day: Option<u64>,
+    ///【是否开启自然人限制】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub natural_person_limit: Option<bool>,
+    ///【可疑账号拦截】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prevent_api_abuse: Option<bool>,
+    ///【是否允许转赠】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transferable: Option<bool>,
+    ///【是否允许分享链接】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shareable: Option<bool>,
+}
+
+/// 事件通知配置
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BusifavorNotifyConfig {
+    ///【事件通知appid】 用于回调通知时，计算返回操作用户的openid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_appid: Option<String>,
+}
+
+/// 样式信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DisplayPatternInfo {
+    ///【使用须知】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    ///【商户logo】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_logo_url: Option<String>,
+    ///【商户名称】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_name: Option<String>,
+    ///【背景颜色】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    ///【券详情图片】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_image_url: Option<String>,
+}
+
+/// 创建商家券
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateBusifavorStockParams {
+    ///【商家券批次名称】 不超过21个字符
+    pub stock_name: String,
+    ///【批次归属商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub belong_merchant: Option<String>,
+    ///【批次备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    ///【适用商品范围】 用来描述批次在哪些商品可用
+    pub goods_name: String,
+    ///【批次类型】
+    pub stock_type: BusifavorStockType,
+    ///【核销规则】
+    pub coupon_use_rule: BusifavorCouponUseRule,
+    ///【发放规则】
+    pub stock_send_rule: BusifavorStockSendRule,
+    ///【商户请求单号】
+    pub out_request_no: String,
+    ///【样式信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_pattern_info: Option<DisplayPatternInfo>,
+    ///【券code模式】
+    pub coupon_code_mode: CouponCodeMode,
+    ///【事件通知配置】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_config: Option<BusifavorNotifyConfig>,
+}
+
+params_builder! {
+    CreateBusifavorStockParamsBuilder => CreateBusifavorStockParams {
+        required {
+            stock_name: String,
+            goods_name: String,
+            stock_type: BusifavorStockType,
+            coupon_use_rule: BusifavorCouponUseRule,
+            stock_send_rule: BusifavorStockSendRule,
+            out_request_no: String => validation::out_bill_no,
+            coupon_code_mode: CouponCodeMode,
+        }
+        optional {
+            belong_merchant: String,
+            comment: String,
+            display_pattern_info: DisplayPatternInfo,
+            notify_config: BusifavorNotifyConfig,
+        }
+        defaults {
+            belong_merchant => mch_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBusifavorStockResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【创建时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub create_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for CreateBusifavorStockResponse {}
+
+/// 修改商家券基本信息，只修改设置了的字段
+#[derive(Serialize, Debug, Clone)]
+pub struct ModifyBusifavorStockParams {
+    ///【商户请求单号】
+    pub out_request_no: String,
+    ///【商家券批次名称】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_name: Option<String>,
+    ///【批次备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    ///【适用商品范围】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_name: Option<String>,
+    ///【样式信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_pattern_info: Option<DisplayPatternInfo>,
+    ///【核销规则】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_use_rule: Option<BusifavorCouponUseRule>,
+    ///【发放规则】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_send_rule: Option<BusifavorStockSendRule>,
+    ///【事件通知配置】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_config: Option<BusifavorNotifyConfig>,
+}
+
+params_builder! {
+    ModifyBusifavorStockParamsBuilder => ModifyBusifavorStockParams {
+        required {
+            out_request_no: String => validation::out_bill_no,
+        }
+        optional {
+            stock_name: String,
+            comment: String,
+            goods_name: String,
+            display_pattern_info: DisplayPatternInfo,
+            coupon_use_rule: BusifavorCouponUseRule,
+            stock_send_rule: BusifavorStockSendRule,
+            notify_config: BusifavorNotifyConfig,
+        }
+        defaults {}
+    }
+}
+
+/// 商家券批次详情
+#[derive(Debug, Deserialize)]
+pub struct BusifavorStockDetail {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【商家券批次名称】
+    pub stock_name: Option<String>,
+    ///【批次归属商户号】
+    pub belong_merchant: Option<String>,
+    ///【批次备注】
+    pub comment: Option<String>,
+    ///【适用商品范围】
+    pub goods_name: Option<String>,
+    ///【批次类型】
+    pub stock_type: Option<BusifavorStockType>,
+    ///【核销规则】
+    pub coupon_use_rule: Option<BusifavorCouponUseRule>,
+    ///【发放规则】
+    pub stock_send_rule: Option<BusifavorStockSendRule>,
+    ///【样式信息】
+    pub display_pattern_info: Option<DisplayPatternInfo>,
+    ///【批次状态】 UNAUDIT、RUNNING、STOPED、PAUSED
+    pub stock_state: Option<String>,
+    ///【券code模式】
+    pub coupon_code_mode: Option<CouponCodeMode>,
+    ///【事件通知配置】
+    pub notify_config: Option<BusifavorNotifyConfig>,
+}
+
+impl ResponseTrait for BusifavorStockDetail {}
+
+/// 上传预存code，券code模式为MERCHANT_UPLOAD时使用
+#[derive(Serialize, Debug, Clone)]
+pub struct UploadCouponCodesParams {
+    ///【券code列表】 单次最多上传200个
+    pub coupon_code_list: Vec<String>,
+    ///【请求业务单据号】
+    pub upload_request_no: String,
+}
+
+params_builder! {
+    UploadCouponCodesParamsBuilder => UploadCouponCodesParams {
+        required {
+            coupon_code_list: Vec<String> => validation::coupon_code_list,
+            upload_request_no: String => validation::out_bill_no,
+        }
+        optional {}
+        defaults {}
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FailCouponCode {
+    ///【上传失败的券code】
+    pub coupon_code: String,
+    ///【上传失败错误码】
+    pub code: String,
+    ///【上传失败错误信息】
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadCouponCodesResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【去重后上传code总数】
+    pub total_count: Option<u32>,
+    ///【上传成功code个数】
+    pub success_count: Option<u32>,
+    ///【上传成功的code列表】
+    #[serde(default)]
+    pub success_codes: Vec<String>,
+    ///【上传成功时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub success_time: Option<DateTime<FixedOffset>>,
+    ///【上传失败code个数】
+    pub fail_count: Option<u32>,
+    ///【上传失败的code及原因】
+    #[serde(default)]
+    pub fail_codes: Vec<FailCouponCode>,
+    ///【已存在的code列表】
+    #[serde(default)]
+    pub exist_codes: Vec<String>,
+    ///【本次请求中重复的code列表】
+    #[serde(default)]
+    pub duplicate_codes: Vec<String>,
+}
+
+impl ResponseTrait for UploadCouponCodesResponse {}
+
+/// 核销用户券
+#[derive(Serialize, Debug, Clone)]
+pub struct UseBusifavorCouponParams {
+    ///【券code】
+    pub coupon_code: String,
+    ///【批次号】 券code模式为WECHATPAY_MODE时可不填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_id: Option<String>,
+    ///【公众账号ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【请求核销时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub use_time: DateTime<FixedOffset>,
+    ///【核销请求单据号】
+    pub use_request_no: String,
+    ///【用户标识】 券code模式为MERCHANT_API或MERCHANT_UPLOAD时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub openid: Option<String>,
+}
+
+params_builder! {
+    UseBusifavorCouponParamsBuilder => UseBusifavorCouponParams {
+        required {
+            coupon_code: String,
+            use_time: DateTime<FixedOffset>,
+            use_request_no: String => validation::out_bill_no,
+        }
+        optional {
+            stock_id: String,
+            appid: String,
+            openid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UseBusifavorCouponResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【用户标识】
+    pub openid: Option<String>,
+    ///【系统核销券成功的时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub wechatpay_use_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for UseBusifavorCouponResponse {}
+
+/// 申请退券，已核销的券退回到用户卡包
+#[derive(Serialize, Debug, Clone)]
+pub struct ReturnBusifavorCouponParams {
+    ///【券code】
+    pub coupon_code: String,
+    ///【批次号】
+    pub stock_id: String,
+    ///【退券请求单据号】
+    pub return_request_no: String,
+}
+
+params_builder! {
+    ReturnBusifavorCouponParamsBuilder => ReturnBusifavorCouponParams {
+        required {
+            coupon_code: String,
+            stock_id: String,
+            return_request_no: String => validation::out_bill_no,
+        }
+        optional {}
+        defaults {}
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReturnBusifavorCouponResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【微信退券成功的时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub wechatpay_return_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for ReturnBusifavorCouponResponse {}
+
+/// 关联订单信息
+#[derive(Serialize, Debug, Clone)]
+pub struct AssociateBusifavorCouponParams {
+    ///【券code】
+    pub coupon_code: String,
+    ///【关联的商户订单号】
+    pub out_trade_no: String,
+    ///【批次号】
+    pub stock_id: String,
+    ///【商户请求单号】
+    pub out_request_no: String,
+}
+
+params_builder! {
+    AssociateBusifavorCouponParamsBuilder => AssociateBusifavorCouponParams {
+        required {
+            coupon_code: String,
+            out_trade_no: String => validation::out_trade_no,
+            stock_id: String,
+            out_request_no: String => validation::out_bill_no,
+        }
+        optional {}
+        defaults {}
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssociateBusifavorCouponResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【关联成功时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub wechatpay_associate_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for AssociateBusifavorCouponResponse {}
+
+/// 小程序发券插件中的一张券
+#[derive(Serialize, Debug, Clone)]
+pub struct SendCouponParam {
+    ///【批次号】
+    pub stock_id: String,
+    ///【发券凭证】 同一个商户号下唯一
+    pub out_request_no: String,
+    ///【券code】 券code模式为MERCHANT_API时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_code: Option<String>,
+}
+
+/// 小程序发券插件的参数，原样传给插件
+#[derive(Serialize, Debug, Clone)]
+pub struct SendCouponPluginParams {
+    ///【发券参数】 最多10张券
+    pub send_coupon_params: Vec<SendCouponParam>,
+    ///【发券商户号】
+    pub send_coupon_merchant: String,
+    ///【签名】 使用APIv3密钥的HMAC-SHA256签名
+    pub sign: String,
+}
+
+impl SendCouponPluginParams {
+    /// 生成发券插件参数，参数名带上券的下标参与签名，例如`stock_id0`、`out_request_no0`
+    pub fn new(pay: &impl WechatPayTrait, send_coupon_params: Vec<SendCouponParam>) -> Self {
+        let send_coupon_merchant = pay.mch_id();
+        let mut map = BTreeMap::new();
+        for (index, param) in send_coupon_params.iter().enumerate() {
+            map.insert(format!("stock_id{}", index), param.stock_id.clone());
+            map.insert(
+                format!("out_request_no{}", index),
+                param.out_request_no.clone(),
+            );
+            if let Some(coupon_code) = &param.coupon_code {
+                map.insert(format!("coupon_code{}", index), coupon_code.clone());
+            }
+        }
+        map.insert(
+            "send_coupon_merchant".to_string(),
+            send_coupon_merchant.clone(),
+        );
+        let v3_key = pay.v3_key();
+        let sign = crate::v2::sign(&map, &v3_key, SignType::HmacSha256);
+        Self {
+            send_coupon_params,
+            send_coupon_merchant,
+            sign,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::busifavor::{SendCouponParam, SendCouponPluginParams, UploadCouponCodesParams};
+    use crate::pay::WechatPay;
+    use crate::sign;
+
+    #[test]
+    fn test_plugin_sign() {
+        let wechat_pay = WechatPay::new(
+            "wxd678efh567hg6787",
+            "10016226",
+            "",
+            "",
+            "0123456789abcdef0123456789abcdef",
+            "",
+        );
+        let params = SendCouponPluginParams::new(
+            &wechat_pay,
+            vec![SendCouponParam {
+                stock_id: "1234567".to_string(),
+                out_request_no: "89560002019101000121".to_string(),
+                coupon_code: None,
+            }],
+        );
+        let expected = sign::hmac_sha256_hex(
+            "0123456789abcdef0123456789abcdef",
+            "out_request_no0=89560002019101000121&send_coupon_merchant=10016226&stock_id0=1234567\
+             &key=0123456789abcdef0123456789abcdef",
+        );
+        assert_eq!(params.sign, expected);
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["send_coupon_merchant"], "10016226");
+        assert!(json["send_coupon_params"][0].get("coupon_code").is_none());
+
+        let error = UploadCouponCodesParams::builder()
+            .coupon_code_list(vec!["code".to_string(); 201])
+            .upload_request_no("100002322019090134234sfdf")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "validation error: coupon_code_list: must contain 1 to 200 codes, got 201"
+        );
+    }
+}
@@ -0,0 +1,490 @@
+//! 消费者投诉2.0
+use crate::datetime;
+use crate::error::{PayError, ValidationError};
+use crate::macros::params_builder;
+use crate::pay::WechatPayTrait;
+use crate::response::ResponseTrait;
+use crate::validation;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// 查询投诉单列表，日期范围不超过30天，请求前会校验
+#[derive(Debug, Clone)]
+pub struct ComplaintListParams {
+    ///【开始日期】 投诉发生的开始日期
+    pub begin_date: NaiveDate,
+    ///【结束日期】 投诉发生的结束日期
+    pub end_date: NaiveDate,
+    ///【分页大小】 最大50，默认10
+    pub limit: Option<u32>,
+    ///【分页开始位置】 默认0
+    pub offset: Option<u32>,
+    ///【被诉商户号】 服务商查询子商户的投诉时设置
+    pub complainted_mchid: Option<String>,
+}
+
+impl ComplaintListParams {
+    pub fn new(begin_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            begin_date,
+            end_date,
+            limit: None,
+            offset: None,
+            complainted_mchid: None,
+        }
+    }
+
+    /// 校验日期范围不超过30天、结束日期不早于开始日期、分页大小为1-50
+    pub fn validate(&self) -> Result<(), PayError> {
+        let mut errors = Vec::new();
+        let days = (self.end_date - self.begin_date).num_days();
+        if days < 0 {
+            errors.push(ValidationError::new(
+                "end_date",
+                "must not be earlier than begin_date",
+            ));
+        } else if days > 30 {
+            errors.push(ValidationError::new(
+                "end_date",
+                format!("date range must be at most 30 days, got {}", days),
+            ));
+        }
+        if let Some(limit) = self.limit {
+            if !(1..=50).contains(&limit) {
+                errors.push(ValidationError::new(
+                    "limit",
+                    format!("must be between 1 and 50, got {}", limit),
+                ));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(PayError::Validation(errors))
+        }
+    }
+
+    pub(crate) fn to_query(&self) -> Result<String, PayError> {
+        self.validate()?;
+        let mut query = format!(
+            "begin_date={}&end_date={}",
+            self.begin_date.format("%Y-%m-%d"),
+            self.end_date.format("%Y-%m-%d")
+        );
+        if let Some(limit) = self.limit {
+            query.push_str(&format!("&limit={}", limit));
+        }
+        if let Some(offset) = self.offset {
+            query.push_str(&format!("&offset={}", offset));
+        }
+        if let Some(complainted_mchid) = &self.complainted_mchid {
+            query.push_str(&format!("&complainted_mchid={}", complainted_mchid));
+        }
+        Ok(query)
+    }
+}
+
+/// 分页查询结果
+#[derive(Debug, Deserialize)]
+pub struct ComplaintPage<T> {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【用户投诉信息详情】
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+    ///【分页大小】
+    pub limit: Option<u32>,
+    ///【分页开始位置】
+    pub offset: Option<u32>,
+    ///【投诉总条数】
+    pub total_count: Option<u32>,
+}
+
+impl<T: serde::de::DeserializeOwned> ResponseTrait for ComplaintPage<T> {}
+
+/// 投诉单状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ComplaintState {
+    ///待处理
+    Pending,
+    ///处理中
+    Processing,
+    ///已处理完成
+    Processed,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComplaintOrderInfo {
+    ///【微信订单号】
+    pub transaction_id: String,
+    ///【商户订单号】
+    pub out_trade_no: String,
+    ///【订单金额】 单位为分
+    pub amount: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComplaintMedia {
+    ///【媒体文件业务类型】 USER_COMPLAINT_IMAGE、OPERATION_IMAGE
+    pub media_type: String,
+    ///【媒体文件请求url】 需要使用`download`签名后下载原始字节
+    #[serde(default)]
+    pub media_url: Vec<String>,
+}
+
+/// 投诉单详情
+#[derive(Debug, Deserialize)]
+pub struct ComplaintDetail {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【投诉单号】
+    pub complaint_id: Option<String>,
+    ///【投诉时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub complaint_time: Option<DateTime<FixedOffset>>,
+    ///【投诉详情】
+    pub complaint_detail: Option<String>,
+    ///【被诉商户号】
+    pub complainted_mchid: Option<String>,
+    ///【投诉单状态】
+    pub complaint_state: Option<ComplaintState>,
+    ///【投诉人联系方式】 使用商户私钥加密，通过`payer_phone`解密
+    pub payer_phone: Option<String>,
+    ///【投诉单关联订单信息】
+    #[serde(default)]
+    pub complaint_order_info: Vec<ComplaintOrderInfo>,
+    ///【投诉单是否已全额退款】
+    pub complaint_full_refunded: Option<bool>,
+    ///【是否有待回复的用户留言】
+    pub incoming_user_response: Option<bool>,
+    ///【用户投诉次数】
+    pub user_complaint_times: Option<u32>,
+    ///【投诉资料列表】
+    #[serde(default)]
+    pub complaint_media_list: Vec<ComplaintMedia>,
+    ///【问题描述】
+    pub problem_description: Option<String>,
+    ///【问题类型】 REFUND、SERVICE_NOT_WORK、OTHERS
+    pub problem_type: Option<String>,
+    ///【申请退款金额】 单位为分
+    pub apply_refund_amount: Option<i64>,
+    ///【用户标签列表】
+    #[serde(default)]
+    pub user_tag_list: Vec<String>,
+}
+
+impl ResponseTrait for ComplaintDetail {}
+
+impl ComplaintDetail {
+    /// 使用商户私钥解密投诉人联系方式
+    pub fn payer_phone(&self, pay: &impl WechatPayTrait) -> Result<Option<String>, PayError> {
+        self.payer_phone
+            .as_deref()
+            .filter(|phone| !phone.is_empty())
+            .map(|phone| pay.decrypt_sensitive(phone))
+            .transpose()
+    }
+}
+
+/// 投诉协商历史
+#[derive(Debug, Clone, Deserialize)]
+pub struct NegotiationHistory {
+    ///【操作流水号】
+    pub log_id: String,
+    ///【操作人】 投诉人、商户、微信支付客服等
+    pub operator: String,
+    ///【操作时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub operate_time: DateTime<FixedOffset>,
+    ///【操作类型】 USER_CREATE_COMPLAINT、MERCHANT_RESPONSE、COMPLAINT_FULL_REFUNDED等
+    pub operate_type: String,
+    ///【操作内容】
+    pub operate_details: Option<String>,
+    ///【图片凭证】
+    #[serde(default)]
+    pub image_list: Vec<String>,
+    ///【投诉资料列表】
+    #[serde(default)]
+    pub complaint_media_list: Vec<ComplaintMedia>,
+}
+
+/// 回复用户
+#[derive(Serialize, Debug, Clone)]
+pub struct ComplaintResponseParams {
+    ///【被诉商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complainted_mchid: Option<String>,
+    ///【回复内容】 不超过200个字符
+    pub response_content: String,
+    ///【回复图片】 通过图片上传接口获取的media_id，最多4张
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_images: Option<Vec<String>>,
+    ///【跳转链接】 商户小程序或H5页面
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jump_url: Option<String>,
+    ///【跳转链接文案】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jump_url_text: Option<String>,
+}
+
+params_builder! {
+    ComplaintResponseParamsBuilder => ComplaintResponseParams {
+        required {
+            response_content: String => validation::response_content,
+        }
+        optional {
+            complainted_mchid: String,
+            response_images: Vec<String>,
+            jump_url: String,
+            jump_url_text: String,
+        }
+        defaults {
+            complainted_mchid => mch_id,
+        }
+    }
+}
+
+impl ComplaintResponseParams {
+    pub fn new<S: AsRef<str>>(response_content: S) -> Self {
+        Self {
+            complainted_mchid: None,
+            response_content: response_content.as_ref().to_string(),
+            response_images: None,
+            jump_url: None,
+            jump_url_text: None,
+        }
+    }
+}
+
+/// 反馈处理完成
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ComplaintCompleteParams {
+    ///【被诉商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complainted_mchid: Option<String>,
+}
+
+params_builder! {
+    ComplaintCompleteParamsBuilder => ComplaintCompleteParams {
+        required {}
+        optional {
+            complainted_mchid: String,
+        }
+        defaults {
+            complainted_mchid => mch_id,
+        }
+    }
+}
+
+/// 更新退款审批结果的动作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RefundProgressAction {
+    ///同意退款
+    Approve,
+    ///拒绝退款
+    Reject,
+}
+
+/// 更新退款审批结果
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdateRefundProgressParams {
+    ///【审批动作】
+    pub action: RefundProgressAction,
+    ///【预计发起退款时间】 同意退款时填写，单位为天
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch_refund_day: Option<u32>,
+    ///【拒绝退款原因】 拒绝退款时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_reason: Option<String>,
+    ///【拒绝退款的举证图片列表】 通过图片上传接口获取的media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_media_list: Option<Vec<String>>,
+    ///【备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+}
+
+params_builder! {
+    UpdateRefundProgressParamsBuilder => UpdateRefundProgressParams {
+        required {
+            action: RefundProgressAction,
+        }
+        optional {
+            launch_refund_day: u32,
+            reject_reason: String,
+            reject_media_list: Vec<String>,
+            remark: String,
+        }
+        defaults {}
+    }
+}
+
+impl UpdateRefundProgressParams {
+    /// 同意退款，`launch_refund_day`天内发起退款
+    pub fn approve(launch_refund_day: u32) -> Self {
+        Self {
+            action: RefundProgressAction::Approve,
+            launch_refund_day: Some(launch_refund_day),
+            reject_reason: None,
+            reject_media_list: None,
+            remark: None,
+        }
+    }
+
+    pub fn reject<S: AsRef<str>>(reject_reason: S) -> Self {
+        Self {
+            action: RefundProgressAction::Reject,
+            launch_refund_day: None,
+            reject_reason: Some(reject_reason.as_ref().to_string()),
+            reject_media_list: None,
+            remark: None,
+        }
+    }
+}
+
+/// 创建或更新投诉通知回调地址
+#[derive(Serialize, Debug, Clone)]
+pub struct ComplaintNotifyUrlParams {
+    ///【通知地址】
+    pub url: String,
+}
+
+params_builder! {
+    ComplaintNotifyUrlParamsBuilder => ComplaintNotifyUrlParams {
+        required {
+            url: String => validation::notify_url,
+        }
+        optional {}
+        defaults {}
+    }
+}
+
+impl ComplaintNotifyUrlParams {
+    pub fn new<S: AsRef<str>>(url: S) -> Self {
+        Self {
+            url: url.as_ref().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComplaintNotifyUrlResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【商户号】
+    pub mchid: Option<String>,
+    ///【通知地址】
+    pub url: Option<String>,
+}
+
+impl ResponseTrait for ComplaintNotifyUrlResponse {}
+
+/// 投诉通知解密后的内容，通知的event_type为COMPLAINT.CREATE、COMPLAINT.STATE_CHANGE等
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComplaintNotifyData {
+    ///【投诉单号】
+    pub complaint_id: String,
+    ///【动作类型】 CREATE_COMPLAINT、CONTINUE_COMPLAINT、USER_RESPONSE、RESPONSE_BY_PLATFORM、
+    /// SELLER_REFUND、MERCHANT_RESPONSE、MERCHANT_CONFIRM_COMPLETE等
+    pub action_type: String,
+    ///【商户订单号】
+    pub out_trade_no: Option<String>,
+    ///【投诉时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub complaint_time: Option<DateTime<FixedOffset>>,
+    ///【订单金额】 单位为分
+    pub amount: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complaint::{ComplaintDetail, ComplaintListParams, ComplaintState};
+    use crate::error::PayError;
+    use crate::pay::WechatPay;
+    use crate::util;
+    use chrono::NaiveDate;
+    use rsa::pkcs8::DecodePublicKey;
+    use rsa::RsaPublicKey;
+
+    const CERT: &str = include_str!("../testdata/apiclient_cert.pem");
+    const PRIVATE_KEY: &str = include_str!("../testdata/apiclient_key.pem");
+
+    fn complaint_detail(payer_phone: &[u8]) -> ComplaintDetail {
+        let body = serde_json::json!({
+            "complaint_id": "200201820200101080076610000",
+            "complaint_time": "2015-05-20T13:29:35.120+08:00",
+            "complaint_detail": "反馈一个重复扣费的问题",
+            "complaint_state": "PENDING",
+            "payer_phone": util::base64_encode(payer_phone),
+            "complaint_order_info": [{
+                "transaction_id": "4200000404201909069117582536",
+                "out_trade_no": "20190906154617947762231",
+                "amount": 3
+            }],
+            "complaint_full_refunded": false,
+            "incoming_user_response": true,
+            "user_complaint_times": 1
+        });
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_complaint_detail() {
+        let detail = complaint_detail(b"");
+        assert_eq!(
+            detail.complaint_id.as_deref(),
+            Some("200201820200101080076610000")
+        );
+        assert_eq!(detail.complaint_state, Some(ComplaintState::Pending));
+        assert_eq!(detail.complaint_order_info[0].amount, 3);
+    }
+
+    #[test]
+    fn test_payer_phone() {
+        let public_key = util::x509_to_pem(CERT.as_bytes()).unwrap();
+        let public_key = RsaPublicKey::from_public_key_pem(&public_key).unwrap();
+        let payer_phone = public_key
+            .encrypt(
+                &mut rsa::rand_core::OsRng,
+                rsa::Oaep::new::<sha1::Sha1>(),
+                b"13800138000",
+            )
+            .unwrap();
+        let detail = complaint_detail(&payer_phone);
+        let wechat_pay = WechatPay::new(
+            "appid",
+            "1230000109",
+            PRIVATE_KEY,
+            "serial_no",
+            "v3_key",
+            "",
+        );
+        assert_eq!(
+            detail.payer_phone(&wechat_pay).unwrap().as_deref(),
+            Some("13800138000")
+        );
+    }
+
+    #[test]
+    fn test_list_query() {
+        let date = |day| NaiveDate::from_ymd_opt(2019, 1, day).unwrap();
+        let mut params = ComplaintListParams::new(date(1), date(30));
+        params.limit = Some(50);
+        assert_eq!(
+            params.to_query().unwrap(),
+            "begin_date=2019-01-01&end_date=2019-01-30&limit=50"
+        );
+
+        params.limit = Some(51);
+        assert!(matches!(params.to_query(), Err(PayError::Validation(_))));
+        let params = ComplaintListParams::new(date(30), date(1));
+        assert!(matches!(params.validate(), Err(PayError::Validation(_))));
+        let end_date = NaiveDate::from_ymd_opt(2019, 2, 1).unwrap();
+        let params = ComplaintListParams::new(date(1), end_date);
+        assert!(matches!(params.validate(), Err(PayError::Validation(_))));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::error::{ConfigError, ValidationError};
+use crate::pay::WechatPay;
+use crate::secret::SecretString;
+use crate::{tls, util, validation};
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::RsaPrivateKey;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// 商户配置，可以从环境变量、TOML/JSON文件加载，也可以组合使用
+/// ```toml
+/// appid = "wxd678efh567hg6787"
+/// mch_id = "1230000109"
+/// private_key_path = "./apiclient_key.pem"
+/// serial_no = "5157F09EFDC096DE15EBE81A47057A7232F1B8E1"
+/// v3_key = "your-32-bytes-api-v3-key........."
+/// notify_url = "https://mydomain.com/pay/notify"
+/// ```
+/// ```rust,no_run
+/// use wechat_pay_rust_sdk::config::WechatPayConfig;
+///
+/// let wechat_pay = WechatPayConfig::from_file("wechat_pay.toml")
+///     .expect("load config error")
+///     .merge(WechatPayConfig::from_env())
+///     .build()
+///     .expect("invalid config");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct WechatPayConfig {
+    pub appid: Option<String>,
+    pub mch_id: Option<String>,
+    ///商户API私钥内容（PEM）
+    pub private_key: Option<SecretString>,
+    ///商户API私钥文件路径，与`private_key`二选一
+    pub private_key_path: Option<PathBuf>,
+    ///商户API证书序列号，不设置时从`cert_path`指定的证书读取
+    pub serial_no: Option<String>,
+    ///商户API证书apiclient_cert.pem路径，设置后请求时使用证书和私钥进行双向认证
+    pub cert_path: Option<PathBuf>,
+    ///APIv3密钥，32字节
+    pub v3_key: Option<SecretString>,
+    pub notify_url: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl WechatPayConfig {
+    /// 从环境变量加载，未设置的变量为`None`。
+    /// `WECHAT_PRIVATE_KEY`可以是PEM内容或私钥文件路径，也可以使用`WECHAT_PRIVATE_KEY_PATH`指定路径
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let mut config = Self {
+            appid: var("WECHAT_APPID"),
+            mch_id: var("WECHAT_MCH_ID"),
+            private_key: None,
+            private_key_path: var("WECHAT_PRIVATE_KEY_PATH").map(PathBuf::from),
+            serial_no: var("WECHAT_SERIAL_NO"),
+            cert_path: var("WECHAT_CERT_PATH").map(PathBuf::from),
+            v3_key: var("WECHAT_V3_KEY").map(SecretString::from),
+            notify_url: var("WECHAT_NOTIFY_URL"),
+            base_url: var("WECHAT_BASE_URL"),
+        };
+        if let Some(private_key) = var("WECHAT_PRIVATE_KEY") {
+            if private_key.trim_start().starts_with("-----BEGIN") {
+                config.private_key = Some(private_key.into());
+            } else if config.private_key_path.is_none() {
+                config.private_key_path = Some(PathBuf::from(private_key));
+            }
+        }
+        config
+    }
+
+    /// 从文件加载，根据扩展名识别格式，`.json`为JSON，其他按TOML解析
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let is_json = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        let mut config = if is_json {
+            Self::from_json_str(&content)?
+        } else {
+            Self::from_toml_str(&content)?
+        };
+        // 私钥和证书路径相对于配置文件所在目录
+        if let Some(dir) = path.parent() {
+            for file_path in [&mut config.private_key_path, &mut config.cert_path]
+                .into_iter()
+                .flatten()
+            {
+                if file_path.is_relative() {
+                    *file_path = dir.join(&*file_path);
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        toml::from_str(content).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    pub fn from_json_str(content: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(content).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    /// 合并配置，`other`中已设置的值覆盖当前值
+    pub fn merge(self, other: WechatPayConfig) -> Self {
+        let (private_key, private_key_path) =
+            if other.private_key.is_some() || other.private_key_path.is_some() {
+                (other.private_key, other.private_key_path)
+            } else {
+                (self.private_key, self.private_key_path)
+            };
+        Self {
+            appid: other.appid.or(self.appid),
+            mch_id: other.mch_id.or(self.mch_id),
+            private_key,
+            private_key_path,
+            serial_no: other.serial_no.or(self.serial_no),
+            cert_path: other.cert_path.or(self.cert_path),
+            v3_key: other.v3_key.or(self.v3_key),
+            notify_url: other.notify_url.or(self.notify_url),
+            base_url: other.base_url.or(self.base_url),
+        }
+    }
+
+    /// 校验配置并创建`WechatPay`，返回所有不合法的配置项
+    pub fn build(self) -> Result<WechatPay, ConfigError> {
+        let mut errors = Vec::new();
+        let required = |field: &str, value: &Option<String>, errors: &mut Vec<ValidationError>| {
+            if value.is_none() {
+                errors.push(ValidationError::new(field, "is required"));
+            }
+        };
+        required("appid", &self.appid, &mut errors);
+        required("mch_id", &self.mch_id, &mut errors);
+        if let Some(mch_id) = &self.mch_id {
+            if !mch_id.bytes().all(|b| b.is_ascii_digit()) {
+                errors.push(ValidationError::new("mch_id", "must only contain digits"));
+            }
+        }
+        let private_key = self.load_private_key(&mut errors);
+        let cert = self.load_cert(&mut errors);
+        let serial_no = match (&self.serial_no, &cert) {
+            (Some(serial_no), _) => {
+                serial(serial_no, &mut errors);
+                Some(serial_no.clone())
+            }
+            (None, Some(cert)) => match util::x509_serial_no(cert) {
+                Ok(serial_no) => Some(serial_no),
+                Err(e) => {
+                    errors.push(ValidationError::new(
+                        "cert_path",
+                        format!("read serial_no from certificate error: {}", e),
+                    ));
+                    None
+                }
+            },
+            (None, None) => {
+                if self.cert_path.is_none() {
+                    errors.push(ValidationError::new("serial_no", "is required"));
+                }
+                None
+            }
+        };
+        match &self.v3_key {
+            Some(v3_key) if v3_key.expose().len() != 32 => errors.push(ValidationError::new(
+                "v3_key",
+                format!("must be 32 bytes, got {}", v3_key.expose().len()),
+            )),
+            Some(_) => {}
+            None => errors.push(ValidationError::new("v3_key", "is required")),
+        }
+        if let Some(notify_url) = &self.notify_url {
+            validation::notify_url("notify_url", notify_url, &mut errors);
+        }
+        let identity = match (&cert, &private_key) {
+            (Some(cert), Some(private_key)) => {
+                match tls::identity_from_pem(cert, private_key.expose().as_bytes()) {
+                    Ok(identity) => Some(identity),
+                    Err(e) => {
+                        errors.push(ValidationError::new("cert_path", e.to_string()));
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        if !errors.is_empty() {
+            return Err(ConfigError::Invalid(errors));
+        }
+        let mut wechat_pay = WechatPay::new(
+            self.appid.clone().unwrap_or_default(),
+            self.mch_id.clone().unwrap_or_default(),
+            String::new(),
+            serial_no.unwrap_or_default(),
+            String::new(),
+            self.notify_url.clone().unwrap_or_default(),
+        );
+        wechat_pay.private_key = private_key.unwrap_or_default();
+        wechat_pay.v3_key = self.v3_key.clone().unwrap_or_default();
+        if let Some(base_url) = &self.base_url {
+            wechat_pay.base_url = base_url.clone();
+        }
+        if let Some(identity) = identity {
+            wechat_pay = wechat_pay.with_identity(identity).map_err(|e| {
+                ConfigError::Invalid(vec![ValidationError::new("cert_path", e.to_string())])
+            })?;
+        }
+        Ok(wechat_pay)
+    }
+
+    fn load_cert(&self, errors: &mut Vec<ValidationError>) -> Option<Vec<u8>> {
+        let path = self.cert_path.as_ref()?;
+        match std::fs::read(path) {
+            Ok(cert) => Some(cert),
+            Err(e) => {
+                errors.push(ValidationError::new(
+                    "cert_path",
+                    format!("read {} error: {}", path.display(), e),
+                ));
+                None
+            }
+        }
+    }
+
+    fn load_private_key(&self, errors: &mut Vec<ValidationError>) -> Option<SecretString> {
+        let (field, private_key) = match (&self.private_key, &self.private_key_path) {
+            (Some(_), Some(_)) => {
+                errors.push(ValidationError::new(
+                    "private_key",
+                    "private_key and private_key_path must not both be set",
+                ));
+                return None;
+            }
+            (Some(private_key), None) => ("private_key", private_key.clone()),
+            (None, Some(path)) => match std::fs::read_to_string(path) {
+                Ok(private_key) => ("private_key_path", SecretString::from(private_key)),
+                Err(e) => {
+                    errors.push(ValidationError::new(
+                        "private_key_path",
+                        format!("read {} error: {}", path.display(), e),
+                    ));
+                    return None;
+                }
+            },
+            (None, None) => {
+                errors.push(ValidationError::new("private_key", "is required"));
+                return None;
+            }
+        };
+        match RsaPrivateKey::from_pkcs8_pem(private_key.expose()) {
+            Ok(_) => Some(private_key),
+            Err(e) => {
+                errors.push(ValidationError::new(
+                    field,
+                    format!("must be a PKCS#8 PEM private key: {}", e),
+                ));
+                None
+            }
+        }
+    }
+}
+
+/// 证书序列号为不超过40位的十六进制字符串
+fn serial(value: &str, errors: &mut Vec<ValidationError>) {
+    if value.is_empty() || value.len() > 40 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        errors.push(ValidationError::new(
+            "serial_no",
+            "must be a hexadecimal string of at most 40 characters",
+        ));
+    }
+}
+
+impl WechatPay {
+    /// 从环境变量加载配置，配置缺失或不合法时返回错误
+    pub fn try_from_env() -> Result<Self, ConfigError> {
+        WechatPayConfig::from_env().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::WechatPayConfig;
+    use crate::error::ConfigError;
+    use crate::pay::WechatPayTrait;
+
+    const PRIVATE_KEY: &str = include_str!("../testdata/apiclient_key.pem");
+
+    #[test]
+    fn test_build_from_toml() {
+        let config = WechatPayConfig::from_toml_str(
+            r#"
+            appid = "wxd678efh567hg6787"
+            mch_id = "1230000109"
+            private_key_path = "testdata/apiclient_key.pem"
+            serial_no = "5157F09EFDC096DE15EBE81A47057A7232F1B8E1"
+            v3_key = "0123456789abcdef0123456789abcdef"
+            "#,
+        )
+        .unwrap();
+        let override_config = WechatPayConfig::from_json_str(
+            r#"{"notify_url":"https://mydomain.com/pay/notify","base_url":"http://127.0.0.1:8080"}"#,
+        )
+        .unwrap();
+        let wechat_pay = config.merge(override_config).build().unwrap();
+        assert_eq!(wechat_pay.mch_id(), "1230000109");
+        assert_eq!(wechat_pay.private_key().as_str(), PRIVATE_KEY);
+        assert_eq!(wechat_pay.notify_url(), "https://mydomain.com/pay/notify");
+        assert_eq!(wechat_pay.base_url(), "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_lists_every_problem() {
+        let config = WechatPayConfig {
+            mch_id: Some("mch".to_string()),
+            private_key: Some("not a key".into()),
+            serial_no: Some("serial-no".to_string()),
+            v3_key: Some("short".into()),
+            notify_url: Some("http://mydomain.com/notify".to_string()),
+            ..Default::default()
+        };
+        let errors = match config.build() {
+            Err(ConfigError::Invalid(errors)) => errors,
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        };
+        let fields = errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                "appid",
+                "mch_id",
+                "private_key",
+                "serial_no",
+                "v3_key",
+                "notify_url"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(matches!(
+            WechatPayConfig::from_toml_str("appid = "),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            WechatPayConfig::from_file("testdata/not_found.toml"),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_serial_no_from_cert() {
+        let config = WechatPayConfig {
+            appid: Some("wxd678efh567hg6787".to_string()),
+            mch_id: Some("1230000109".to_string()),
+            private_key_path: Some("testdata/apiclient_key.pem".into()),
+            cert_path: Some("testdata/apiclient_cert.pem".into()),
+            v3_key: Some("0123456789abcdef0123456789abcdef".into()),
+            ..Default::default()
+        };
+        let wechat_pay = config.build().unwrap();
+        assert_eq!(
+            wechat_pay.serial_no(),
+            "5157F09EFDC096DE15EBE81A47057A7232F1B8E1"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat, Timelike, Utc};
+use std::time::Duration;
+
+/// 北京时间（东八区），微信支付接口中的时间均使用该时区
+pub fn shanghai() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).unwrap()
+}
+
+/// 当前的北京时间
+pub fn now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&shanghai())
+}
+
+/// 从当前时间起经过`duration`后的北京时间，精确到秒，用于计算订单失效时间
+pub fn expire_in(duration: Duration) -> DateTime<FixedOffset> {
+    let duration = chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX);
+    let expire = now()
+        .checked_add_signed(duration)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC.with_timezone(&shanghai()));
+    expire.with_nanosecond(0).unwrap_or(expire)
+}
+
+/// 按微信支付要求的格式输出，例如`2015-05-20T13:29:35+08:00`
+pub fn format(time: &DateTime<FixedOffset>) -> String {
+    time.with_timezone(&shanghai())
+        .to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// 解析rfc3339格式的时间
+pub fn parse(value: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(value)
+}
+
+/// 用于`#[serde(with = "crate::datetime::rfc3339")]`
+pub mod rfc3339 {
+    use chrono::{DateTime, FixedOffset};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format(time))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<FixedOffset>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// 用于`#[serde(with = "crate::datetime::rfc3339_option")]`，需要配合`#[serde(default)]`使用
+pub mod rfc3339_option {
+    use chrono::{DateTime, FixedOffset};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match time {
+            Some(time) => super::rfc3339::serialize(time, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<FixedOffset>>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(value) if !value.is_empty() => super::parse(&value)
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datetime;
+    use chrono::{DateTime, FixedOffset, Timelike};
+    use serde::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    struct Sample {
+        #[serde(with = "datetime::rfc3339")]
+        time: DateTime<FixedOffset>,
+        #[serde(
+            default,
+            with = "datetime::rfc3339_option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        time_expire: Option<DateTime<FixedOffset>>,
+    }
+
+    #[test]
+    fn test_format() {
+        let sample: Sample = serde_json::from_str(r#"{"time":"2015-05-20T05:29:35Z"}"#).unwrap();
+        assert!(sample.time_expire.is_none());
+        assert_eq!(
+            serde_json::to_string(&sample).unwrap(),
+            r#"{"time":"2015-05-20T13:29:35+08:00"}"#
+        );
+        let sample: Sample = serde_json::from_str(
+            r#"{"time":"2015-05-20T13:29:35.120+08:00","time_expire":"2015-05-20T14:29:35+08:00"}"#,
+        )
+        .unwrap();
+        assert_eq!(datetime::format(&sample.time), "2015-05-20T13:29:35+08:00");
+        assert_eq!(
+            datetime::format(&sample.time_expire.unwrap()),
+            "2015-05-20T14:29:35+08:00"
+        );
+    }
+
+    #[test]
+    fn test_expire_in() {
+        let before = datetime::now();
+        let expire = datetime::expire_in(Duration::from_secs(30 * 60));
+        assert_eq!(expire.offset(), &datetime::shanghai());
+        assert_eq!(expire.nanosecond(), 0);
+        let minutes = (expire - before).num_minutes();
+        assert!((29..=30).contains(&minutes));
+    }
+}
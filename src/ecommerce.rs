@@ -0,0 +1,1167 @@
+//! 电商收付通，二级商户进件、合单支付、分账、退款、余额查询、提现和电商账单下载。
+//! 所有接口由电商平台（服务商）发起，使用平台商户号的API证书签名，二级商户通过`sub_mchid`指定。
+use crate::datetime;
+use crate::error::{PayError, ValidationError};
+use crate::macros::params_builder;
+use crate::money::Money;
+use crate::pay::WechatPayTrait;
+use crate::response::ResponseTrait;
+use crate::sign::{self, PlatformPublicKey};
+use crate::validation;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+fn encrypt(key: &PlatformPublicKey, value: &mut String) -> Result<(), PayError> {
+    *value = key.encrypt(value.as_str())?;
+    Ok(())
+}
+
+fn encrypt_option(key: &PlatformPublicKey, value: &mut Option<String>) -> Result<(), PayError> {
+    if let Some(value) = value {
+        encrypt(key, value)?;
+    }
+    Ok(())
+}
+
+/// 二级商户营业执照
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceBusinessLicenseInfo {
+    ///【营业执照扫描件】 media_id
+    pub business_license_copy: String,
+    ///【营业执照注册号】
+    pub business_license_number: String,
+    ///【商户名称】
+    pub merchant_name: String,
+    ///【经营者/法定代表人姓名】
+    pub legal_person: String,
+    ///【注册地址】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company_address: Option<String>,
+    ///【营业期限】 例如["2014-01-01","长期"]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_time: Option<String>,
+}
+
+/// 二级商户经营者/法人身份证
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceIdCardInfo {
+    ///【身份证人像面照片】 media_id
+    pub id_card_copy: String,
+    ///【身份证国徽面照片】 media_id
+    pub id_card_national: String,
+    ///【身份证姓名】 加密
+    pub id_card_name: String,
+    ///【身份证号码】 加密
+    pub id_card_number: String,
+    ///【身份证居住地址】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_card_address: Option<String>,
+    ///【身份证有效期开始时间】
+    pub id_card_valid_time_begin: String,
+    ///【身份证有效期结束时间】 长期时填写“长期”
+    pub id_card_valid_time: String,
+}
+
+impl EcommerceIdCardInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.id_card_name)?;
+        encrypt(key, &mut self.id_card_number)?;
+        encrypt_option(key, &mut self.id_card_address)
+    }
+}
+
+/// 二级商户结算账户
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceAccountInfo {
+    ///【账户类型】 75：对私账户，74：对公账户
+    pub bank_account_type: String,
+    ///【开户银行】
+    pub account_bank: String,
+    ///【开户名称】 加密
+    pub account_name: String,
+    ///【开户银行省市编码】
+    pub bank_address_code: String,
+    ///【开户银行联行号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_branch_id: Option<String>,
+    ///【开户银行全称（含支行）】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_name: Option<String>,
+    ///【银行账号】 加密
+    pub account_number: String,
+}
+
+impl EcommerceAccountInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.account_name)?;
+        encrypt(key, &mut self.account_number)
+    }
+}
+
+/// 二级商户超级管理员
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceContactInfo {
+    ///【超级管理员类型】 65：经营者/法人，66：经办人
+    pub contact_type: String,
+    ///【超级管理员姓名】 加密
+    pub contact_name: String,
+    ///【超级管理员身份证件号码】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_id_card_number: Option<String>,
+    ///【超级管理员手机】 加密
+    pub mobile_phone: String,
+    ///【超级管理员邮箱】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_email: Option<String>,
+}
+
+impl EcommerceContactInfo {
+    fn encrypt(&mut self, key: &PlatformPublicKey) -> Result<(), PayError> {
+        encrypt(key, &mut self.contact_name)?;
+        encrypt_option(key, &mut self.contact_id_card_number)?;
+        encrypt(key, &mut self.mobile_phone)?;
+        encrypt_option(key, &mut self.contact_email)
+    }
+}
+
+/// 二级商户店铺信息
+#[derive(Serialize, Debug, Clone)]
+pub struct SalesSceneInfo {
+    ///【店铺名称】
+    pub store_name: String,
+    ///【店铺链接】 与店铺二维码二选一
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_url: Option<String>,
+    ///【店铺二维码】 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_qr_code: Option<String>,
+    ///【小程序AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mini_program_sub_appid: Option<String>,
+}
+
+/// 二级商户进件申请，敏感字段填写明文，提交时使用平台证书加密
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceApplymentParams {
+    ///【业务申请编号】
+    pub out_request_no: String,
+    ///【主体类型】 2401：小微商户，2500：个人卖家，4：个体工商户，2：企业
+    pub organization_type: String,
+    ///【是否金融机构】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finance_institution: Option<bool>,
+    ///【营业执照信息】 主体为个体工商户、企业时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_license_info: Option<EcommerceBusinessLicenseInfo>,
+    ///【经营者/法人证件类型】 IDENTIFICATION_TYPE_MAINLAND_IDCARD等
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_doc_type: Option<String>,
+    ///【经营者/法人身份证信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_card_info: Option<EcommerceIdCardInfo>,
+    ///【是否填写结算账户信息】
+    pub need_account_info: bool,
+    ///【结算账户信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_info: Option<EcommerceAccountInfo>,
+    ///【超级管理员信息】
+    pub contact_info: EcommerceContactInfo,
+    ///【店铺信息】
+    pub sales_scene_info: SalesSceneInfo,
+    ///【商户简称】
+    pub merchant_shortname: String,
+    ///【特殊资质】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualifications: Option<Vec<String>>,
+    ///【补充材料】 media_id列表
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_addition_pics: Option<Vec<String>>,
+    ///【补充说明】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_addition_desc: Option<String>,
+}
+
+params_builder! {
+    EcommerceApplymentParamsBuilder => EcommerceApplymentParams {
+        required {
+            out_request_no: String,
+            organization_type: String,
+            need_account_info: bool,
+            contact_info: EcommerceContactInfo,
+            sales_scene_info: SalesSceneInfo,
+            merchant_shortname: String,
+        }
+        optional {
+            finance_institution: bool,
+            business_license_info: EcommerceBusinessLicenseInfo,
+            id_doc_type: String,
+            id_card_info: EcommerceIdCardInfo,
+            account_info: EcommerceAccountInfo,
+            qualifications: Vec<String>,
+            business_addition_pics: Vec<String>,
+            business_addition_desc: String,
+        }
+        defaults {}
+    }
+}
+
+impl EcommerceApplymentParams {
+    /// 使用平台证书加密所有敏感字段
+    pub(crate) fn encrypt(mut self, key: &PlatformPublicKey) -> Result<Self, PayError> {
+        if let Some(info) = &mut self.id_card_info {
+            info.encrypt(key)?;
+        }
+        if let Some(info) = &mut self.account_info {
+            info.encrypt(key)?;
+        }
+        self.contact_info.encrypt(key)?;
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EcommerceApplymentResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【微信支付申请单号】
+    pub applyment_id: Option<u64>,
+    ///【业务申请编号】
+    pub out_request_no: Option<String>,
+}
+
+impl ResponseTrait for EcommerceApplymentResponse {}
+
+/// 二级商户进件驳回原因
+#[derive(Debug, Clone, Deserialize)]
+pub struct EcommerceAuditDetail {
+    ///【参数名称】
+    pub param_name: Option<String>,
+    ///【驳回原因】
+    pub reject_reason: Option<String>,
+}
+
+/// 二级商户进件申请状态
+#[derive(Debug, Deserialize)]
+pub struct EcommerceApplymentStateResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【申请状态】 CHECKING、ACCOUNT_NEED_VERIFY、AUDITING、REJECTED、NEED_SIGN、FINISH、FROZEN、CANCELED
+    pub applyment_state: Option<String>,
+    ///【申请状态描述】
+    pub applyment_state_desc: Option<String>,
+    ///【签约状态】 UNSIGNED、SIGNED、NOT_SIGNABLE
+    pub sign_state: Option<String>,
+    ///【签约链接】
+    pub sign_url: Option<String>,
+    ///【电商平台二级商户号】 申请完成后返回
+    pub sub_mchid: Option<String>,
+    ///【驳回原因详情】
+    #[serde(default)]
+    pub audit_detail: Vec<EcommerceAuditDetail>,
+    ///【法人验证链接】
+    pub legal_validation_url: Option<String>,
+    ///【业务申请编号】
+    pub out_request_no: Option<String>,
+    ///【微信支付申请单号】
+    pub applyment_id: Option<u64>,
+}
+
+impl ResponseTrait for EcommerceApplymentStateResponse {}
+
+/// 合单支付的调起方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombinePayType {
+    Jsapi,
+    App,
+    H5,
+    Native,
+}
+
+impl CombinePayType {
+    pub(crate) fn path(&self) -> &str {
+        match self {
+            CombinePayType::Jsapi => "jsapi",
+            CombinePayType::App => "app",
+            CombinePayType::H5 => "h5",
+            CombinePayType::Native => "native",
+        }
+    }
+}
+
+/// 子单金额
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CombineAmount {
+    ///【标价金额】 单位为分
+    pub total_amount: Money,
+    ///【标价币种】
+    pub currency: String,
+    ///【现金支付金额】 查询和通知中返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_amount: Option<Money>,
+}
+
+impl From<Money> for CombineAmount {
+    fn from(total_amount: Money) -> Self {
+        Self {
+            total_amount,
+            currency: "CNY".to_string(),
+            payer_amount: None,
+        }
+    }
+}
+
+/// 子单结算信息
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CombineSettleInfo {
+    ///【是否指定分账】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profit_sharing: Option<bool>,
+    ///【补差金额】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsidy_amount: Option<Money>,
+}
+
+/// 合单支付子单，每个子单对应一个二级商户
+#[derive(Serialize, Debug, Clone)]
+pub struct CombineSubOrder {
+    ///【子单商户号】 电商平台商户号
+    pub mchid: String,
+    ///【附加数据】
+    pub attach: String,
+    ///【订单金额】
+    pub amount: CombineAmount,
+    ///【子单商户订单号】
+    pub out_trade_no: String,
+    ///【二级商户号】
+    pub sub_mchid: String,
+    ///【商品描述】
+    pub description: String,
+    ///【结算信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settle_info: Option<CombineSettleInfo>,
+}
+
+impl CombineSubOrder {
+    /// `mchid`为电商平台商户号，附加数据为空
+    pub fn new<S: AsRef<str>>(
+        mchid: S,
+        sub_mchid: S,
+        out_trade_no: S,
+        description: S,
+        amount: Money,
+    ) -> Self {
+        Self {
+            mchid: mchid.as_ref().to_string(),
+            attach: String::new(),
+            amount: amount.into(),
+            out_trade_no: out_trade_no.as_ref().to_string(),
+            sub_mchid: sub_mchid.as_ref().to_string(),
+            description: description.as_ref().to_string(),
+            settle_info: None,
+        }
+    }
+
+    /// 该子单需要分账
+    pub fn with_profit_sharing(mut self) -> Self {
+        self.settle_info = Some(CombineSettleInfo {
+            profit_sharing: Some(true),
+            subsidy_amount: None,
+        });
+        self
+    }
+}
+
+/// 合单支付者
+#[derive(Serialize, Debug, Clone)]
+pub struct CombinePayerInfo {
+    ///【用户标识】 JSAPI支付时必填
+    pub openid: String,
+}
+
+/// 合单支付场景信息
+#[derive(Serialize, Debug, Clone)]
+pub struct CombineSceneInfo {
+    ///【用户终端IP】
+    pub payer_client_ip: String,
+    ///【商户端设备号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    ///【H5场景信息】 H5支付时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub h5_info: Option<crate::model::H5Info>,
+}
+
+/// 合单下单
+#[derive(Serialize, Debug, Clone)]
+pub struct CombinePayParams {
+    ///【合单商户AppID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine_appid: Option<String>,
+    ///【合单商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine_mchid: Option<String>,
+    ///【合单商户订单号】
+    pub combine_out_trade_no: String,
+    ///【场景信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_info: Option<CombineSceneInfo>,
+    ///【子单信息】 最多支持50个子单
+    pub sub_orders: Vec<CombineSubOrder>,
+    ///【支付者】 JSAPI支付时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine_payer_info: Option<CombinePayerInfo>,
+    ///【交易起始时间】
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "datetime::rfc3339_option"
+    )]
+    pub time_start: Option<DateTime<FixedOffset>>,
+    ///【交易结束时间】
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "datetime::rfc3339_option"
+    )]
+    pub time_expire: Option<DateTime<FixedOffset>>,
+    ///【通知地址】 不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
+}
+
+params_builder! {
+    CombinePayParamsBuilder => CombinePayParams {
+        required {
+            combine_out_trade_no: String => validation::out_trade_no,
+            sub_orders: Vec<CombineSubOrder> => check_sub_orders,
+        }
+        optional {
+            combine_appid: String,
+            combine_mchid: String,
+            scene_info: CombineSceneInfo,
+            combine_payer_info: CombinePayerInfo,
+            time_start: DateTime<FixedOffset>,
+            time_expire: DateTime<FixedOffset>,
+            notify_url: String => validation::notify_url,
+        }
+        defaults {
+            combine_appid => appid,
+            combine_mchid => mch_id,
+            notify_url => notify_url,
+        }
+    }
+}
+
+/// 合单支付最多包含50个子单
+fn check_sub_orders(field: &str, value: &[CombineSubOrder], errors: &mut Vec<ValidationError>) {
+    if value.is_empty() || value.len() > 50 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must contain 1 to 50 sub orders, got {}", value.len()),
+        ));
+    }
+}
+
+/// 合单下单结果，JSAPI、APP返回prepay_id，H5返回h5_url，Native返回code_url
+#[derive(Debug, Deserialize)]
+pub struct CombinePayResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub prepay_id: Option<String>,
+    pub h5_url: Option<String>,
+    pub code_url: Option<String>,
+}
+
+impl ResponseTrait for CombinePayResponse {}
+
+/// 合单子单支付结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct CombineSubOrderResult {
+    pub mchid: Option<String>,
+    pub sub_mchid: Option<String>,
+    pub out_trade_no: Option<String>,
+    ///【微信支付订单号】
+    pub transaction_id: Option<String>,
+    ///【交易类型】
+    pub trade_type: Option<String>,
+    ///【交易状态】
+    pub trade_state: Option<String>,
+    ///【付款银行】
+    pub bank_type: Option<String>,
+    pub attach: Option<String>,
+    ///【支付完成时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub success_time: Option<DateTime<FixedOffset>>,
+    pub amount: Option<CombineAmount>,
+}
+
+/// 合单订单，查询合单和合单支付通知都返回该结构
+#[derive(Debug, Deserialize)]
+pub struct CombineOrder {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub combine_appid: Option<String>,
+    pub combine_mchid: Option<String>,
+    pub combine_out_trade_no: Option<String>,
+    #[serde(default)]
+    pub sub_orders: Vec<CombineSubOrderResult>,
+}
+
+impl ResponseTrait for CombineOrder {}
+
+/// 合单支付（TRANSACTION.SUCCESS）通知解密后的内容
+pub type CombineNotifyData = CombineOrder;
+
+/// 关单子单
+#[derive(Serialize, Debug, Clone)]
+pub struct CloseCombineSubOrder {
+    pub mchid: String,
+    pub out_trade_no: String,
+    pub sub_mchid: String,
+}
+
+/// 合单关单
+#[derive(Serialize, Debug, Clone)]
+pub struct CloseCombineOrderParams {
+    ///【合单商户AppID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine_appid: Option<String>,
+    ///【子单信息】
+    pub sub_orders: Vec<CloseCombineSubOrder>,
+}
+
+params_builder! {
+    CloseCombineOrderParamsBuilder => CloseCombineOrderParams {
+        required {
+            sub_orders: Vec<CloseCombineSubOrder>,
+        }
+        optional {
+            combine_appid: String,
+        }
+        defaults {
+            combine_appid => appid,
+        }
+    }
+}
+
+/// 分账接收方
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceProfitSharingReceiver {
+    ///【分账接收方类型】 MERCHANT_ID、PERSONAL_OPENID
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    ///【分账接收方账号】
+    pub receiver_account: String,
+    ///【分账个人接收方姓名】 加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receiver_name: Option<String>,
+    ///【分账金额】
+    pub amount: Money,
+    ///【分账描述】
+    pub description: String,
+}
+
+/// 请求分账
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceProfitSharingParams {
+    ///【公众账号ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【二级商户号】
+    pub sub_mchid: String,
+    ///【微信订单号】
+    pub transaction_id: String,
+    ///【商户分账单号】
+    pub out_order_no: String,
+    ///【分账接收方列表】
+    pub receivers: Vec<EcommerceProfitSharingReceiver>,
+    ///【是否分账完成】 true时剩余金额解冻给二级商户
+    pub finish: bool,
+}
+
+params_builder! {
+    EcommerceProfitSharingParamsBuilder => EcommerceProfitSharingParams {
+        required {
+            sub_mchid: String,
+            transaction_id: String,
+            out_order_no: String => validation::out_trade_no,
+            receivers: Vec<EcommerceProfitSharingReceiver>,
+            finish: bool,
+        }
+        optional {
+            appid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+impl EcommerceProfitSharingParams {
+    /// 是否包含需要加密的接收方姓名
+    pub(crate) fn has_receiver_name(&self) -> bool {
+        self.receivers
+            .iter()
+            .any(|receiver| receiver.receiver_name.is_some())
+    }
+
+    /// 使用平台证书加密接收方姓名
+    pub(crate) fn encrypt(mut self, key: &PlatformPublicKey) -> Result<Self, PayError> {
+        for receiver in self.receivers.iter_mut() {
+            encrypt_option(key, &mut receiver.receiver_name)?;
+        }
+        Ok(self)
+    }
+}
+
+/// 分账接收方结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfitSharingReceiverResult {
+    ///【分账接收方类型】
+    #[serde(rename = "type")]
+    pub receiver_type: Option<String>,
+    ///【分账接收方账号】
+    pub receiver_account: Option<String>,
+    ///【分账接收商户号】
+    pub receiver_mchid: Option<String>,
+    ///【分账金额】
+    pub amount: Option<Money>,
+    ///【分账描述】
+    pub description: Option<String>,
+    ///【分账结果】 PENDING、SUCCESS、CLOSED
+    pub result: Option<String>,
+    ///【分账失败原因】
+    pub fail_reason: Option<String>,
+    ///【分账明细单号】
+    pub detail_id: Option<String>,
+    ///【完成时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub finish_time: Option<DateTime<FixedOffset>>,
+}
+
+/// 分账单，请求分账和查询分账结果返回该结构
+#[derive(Debug, Deserialize)]
+pub struct EcommerceProfitSharingOrder {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub sub_mchid: Option<String>,
+    pub transaction_id: Option<String>,
+    pub out_order_no: Option<String>,
+    ///【微信分账单号】
+    pub order_id: Option<String>,
+    ///【分账单状态】 PROCESSING、FINISHED
+    pub status: Option<String>,
+    #[serde(default)]
+    pub receivers: Vec<ProfitSharingReceiverResult>,
+    ///【关单原因】
+    pub close_reason: Option<String>,
+}
+
+impl ResponseTrait for EcommerceProfitSharingOrder {}
+
+/// 完结分账，剩余待分金额解冻给二级商户
+#[derive(Serialize, Debug, Clone)]
+pub struct FinishProfitSharingParams {
+    pub sub_mchid: String,
+    pub transaction_id: String,
+    pub out_order_no: String,
+    ///【分账描述】
+    pub description: String,
+}
+
+params_builder! {
+    FinishProfitSharingParamsBuilder => FinishProfitSharingParams {
+        required {
+            sub_mchid: String,
+            transaction_id: String,
+            out_order_no: String => validation::out_trade_no,
+            description: String,
+        }
+        optional {}
+        defaults {}
+    }
+}
+
+/// 请求分账回退
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfitSharingReturnParams {
+    ///【二级商户号】
+    pub sub_mchid: String,
+    ///【微信分账单号】 与商户分账单号二选一
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    ///【商户分账单号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_order_no: Option<String>,
+    ///【商户回退单号】
+    pub out_return_no: String,
+    ///【回退商户号】 只能对原分账请求中成功分给商户接收方进行回退
+    pub return_mchid: String,
+    ///【回退金额】
+    pub amount: Money,
+    ///【回退描述】
+    pub description: String,
+}
+
+params_builder! {
+    ProfitSharingReturnParamsBuilder => ProfitSharingReturnParams {
+        required {
+            sub_mchid: String,
+            out_return_no: String => validation::out_trade_no,
+            return_mchid: String,
+            amount: Money => validation::money,
+            description: String,
+        }
+        optional {
+            order_id: String,
+            out_order_no: String,
+        }
+        defaults {}
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProfitSharingReturnResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub sub_mchid: Option<String>,
+    pub order_id: Option<String>,
+    pub out_order_no: Option<String>,
+    pub out_return_no: Option<String>,
+    ///【微信回退单号】
+    pub return_no: Option<String>,
+    pub return_mchid: Option<String>,
+    pub amount: Option<Money>,
+    ///【回退结果】 PROCESSING、SUCCESS、FAILED
+    pub result: Option<String>,
+    pub fail_reason: Option<String>,
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub finish_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for ProfitSharingReturnResponse {}
+
+/// 退款金额
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceRefundAmount {
+    ///【退款金额】
+    pub refund: Money,
+    ///【原订单金额】
+    pub total: Money,
+    ///【退款币种】
+    pub currency: String,
+}
+
+impl EcommerceRefundAmount {
+    pub fn new(refund: Money, total: Money) -> Self {
+        Self {
+            refund,
+            total,
+            currency: "CNY".to_string(),
+        }
+    }
+}
+
+/// 申请退款，微信订单号和商户订单号二选一
+#[derive(Serialize, Debug, Clone)]
+pub struct EcommerceRefundParams {
+    ///【二级商户号】
+    pub sub_mchid: String,
+    ///【电商平台AppID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sp_appid: Option<String>,
+    ///【二级商户AppID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_appid: Option<String>,
+    ///【微信订单号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    ///【商户订单号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_trade_no: Option<String>,
+    ///【商户退款单号】
+    pub out_refund_no: String,
+    ///【退款原因】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    ///【订单金额】
+    pub amount: EcommerceRefundAmount,
+    ///【退款结果回调地址】 不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
+}
+
+params_builder! {
+    EcommerceRefundParamsBuilder => EcommerceRefundParams {
+        required {
+            sub_mchid: String,
+            out_refund_no: String => validation::out_trade_no,
+            amount: EcommerceRefundAmount,
+        }
+        optional {
+            sp_appid: String,
+            sub_appid: String,
+            transaction_id: String,
+            out_trade_no: String => validation::out_trade_no,
+            reason: String,
+            notify_url: String => validation::notify_url,
+        }
+        defaults {
+            sp_appid => appid,
+            notify_url => notify_url,
+        }
+        check { EcommerceRefundParams::check }
+    }
+}
+
+impl EcommerceRefundParams {
+    fn check(&self, errors: &mut Vec<ValidationError>) {
+        if self.transaction_id.is_none() && self.out_trade_no.is_none() {
+            errors.push(ValidationError::new(
+                "transaction_id",
+                "transaction_id or out_trade_no is required",
+            ));
+        }
+    }
+}
+
+/// 退款金额明细
+#[derive(Debug, Clone, Deserialize)]
+pub struct EcommerceRefundAmountDetail {
+    pub refund: Option<Money>,
+    ///【用户退款金额】
+    pub payer_refund: Option<Money>,
+    ///【优惠退款金额】
+    pub discount_refund: Option<Money>,
+    pub currency: Option<String>,
+}
+
+/// 退款单，申请退款、查询退款和退款通知返回该结构
+#[derive(Debug, Deserialize)]
+pub struct EcommerceRefund {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub sub_mchid: Option<String>,
+    ///【微信退款单号】
+    pub refund_id: Option<String>,
+    pub out_refund_no: Option<String>,
+    pub transaction_id: Option<String>,
+    pub out_trade_no: Option<String>,
+    ///【退款状态】 SUCCESS、REFUNDCLOSE、PROCESSING、ABNORMAL
+    pub status: Option<String>,
+    ///【退款入账账户】
+    pub user_received_account: Option<String>,
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub create_time: Option<DateTime<FixedOffset>>,
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub success_time: Option<DateTime<FixedOffset>>,
+    pub amount: Option<EcommerceRefundAmountDetail>,
+}
+
+impl ResponseTrait for EcommerceRefund {}
+
+/// 退款（REFUND.SUCCESS等）通知解密后的内容
+pub type EcommerceRefundNotifyData = EcommerceRefund;
+
+/// 账户类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    /// 基本账户
+    Basic,
+    /// 运营账户
+    Operation,
+    /// 手续费账户
+    Fees,
+}
+
+impl AccountType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccountType::Basic => "BASIC",
+            AccountType::Operation => "OPERATION",
+            AccountType::Fees => "FEES",
+        }
+    }
+}
+
+/// 账户余额
+#[derive(Debug, Deserialize)]
+pub struct FundBalance {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【二级商户号】 查询二级商户余额时返回
+    pub sub_mchid: Option<String>,
+    pub account_type: Option<String>,
+    ///【可用余额】
+    pub available_amount: Option<Money>,
+    ///【不可用余额】
+    pub pending_amount: Option<Money>,
+}
+
+impl ResponseTrait for FundBalance {}
+
+/// 二级商户余额提现
+#[derive(Serialize, Debug, Clone)]
+pub struct WithdrawParams {
+    ///【二级商户号】
+    pub sub_mchid: String,
+    ///【商户提现单号】
+    pub out_request_no: String,
+    ///【提现金额】
+    pub amount: Money,
+    ///【提现备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+    ///【银行附言】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_memo: Option<String>,
+    ///【出款账户类型】 BASIC、OPERATION、FEES
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_type: Option<String>,
+}
+
+params_builder! {
+    WithdrawParamsBuilder => WithdrawParams {
+        required {
+            sub_mchid: String,
+            out_request_no: String => validation::out_trade_no,
+            amount: Money => validation::money,
+        }
+        optional {
+            remark: String,
+            bank_memo: String,
+            account_type: String,
+        }
+        defaults {}
+    }
+}
+
+/// 提现单，发起提现和查询提现状态返回该结构
+#[derive(Debug, Deserialize)]
+pub struct Withdraw {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub sub_mchid: Option<String>,
+    pub sp_mchid: Option<String>,
+    ///【提现单状态】 CREATE_SUCCESS、SUCCESS、FAIL、REFUND、CLOSE、INIT
+    pub status: Option<String>,
+    ///【微信支付提现单号】
+    pub withdraw_id: Option<String>,
+    pub out_request_no: Option<String>,
+    pub amount: Option<Money>,
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub create_time: Option<DateTime<FixedOffset>>,
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub update_time: Option<DateTime<FixedOffset>>,
+    ///【失败原因】
+    pub reason: Option<String>,
+    pub remark: Option<String>,
+    pub bank_memo: Option<String>,
+    pub account_type: Option<String>,
+    ///【入账银行账号后四位】
+    pub account_number: Option<String>,
+    pub account_bank: Option<String>,
+    pub bank_name: Option<String>,
+}
+
+impl ResponseTrait for Withdraw {}
+
+/// 加密的账单文件
+#[derive(Debug, Clone, Deserialize)]
+pub struct EncryptedBill {
+    ///【账单文件序号】
+    pub bill_sequence: Option<u32>,
+    ///【下载地址】 有效期30秒
+    pub download_url: String,
+    ///【加密密钥】 使用商户API证书公钥加密的AES密钥
+    pub encrypt_key: String,
+    ///【哈希类型】 SHA1
+    pub hash_type: Option<String>,
+    ///【哈希值】 原始账单的摘要值
+    pub hash_value: Option<String>,
+    ///【随机字符串】 解密账单使用的nonce
+    pub nonce: String,
+}
+
+impl EncryptedBill {
+    /// 使用商户私钥解密AES密钥，再用AEAD_AES_256_GCM解密账单并校验哈希值
+    pub fn decrypt(&self, pay: &impl WechatPayTrait, content: &[u8]) -> Result<Vec<u8>, PayError> {
+        let key = zeroize::Zeroizing::new(pay.decrypt_sensitive(&self.encrypt_key)?);
+        if key.len() != 32 || self.nonce.len() != 12 {
+            return Err(PayError::DecryptError(
+                "bill key length must be 32 and nonce length must be 12".to_string(),
+            ));
+        }
+        let cipher = Aes256Gcm::new(key.as_bytes().into());
+        let payload = Payload {
+            msg: content,
+            aad: b"",
+        };
+        let plaintext = cipher
+            .decrypt(self.nonce.as_bytes().into(), payload)
+            .map_err(|e| PayError::DecryptError(e.to_string()))?;
+        if let Some(hash_value) = &self.hash_value {
+            let actual = sign::sha1_hex(&plaintext);
+            if !actual.eq_ignore_ascii_case(hash_value) {
+                return Err(PayError::VerifyError(format!(
+                    "bill hash mismatch, expected {} got {}",
+                    hash_value, actual
+                )));
+            }
+        }
+        Ok(plaintext)
+    }
+}
+
+/// 申请二级商户资金账单的结果
+#[derive(Debug, Deserialize)]
+pub struct EcommerceBillResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【下载信息总数】
+    pub download_bill_count: Option<u32>,
+    ///【下载信息明细】
+    #[serde(default)]
+    pub download_bill_list: Vec<EncryptedBill>,
+}
+
+impl ResponseTrait for EcommerceBillResponse {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ecommerce::{
+        CombinePayParams, CombineSubOrder, EcommerceProfitSharingParams,
+        EcommerceProfitSharingReceiver, EcommerceRefundAmount, EcommerceRefundParams,
+        EncryptedBill,
+    };
+    use crate::model::ParamsTrait;
+    use crate::money::Money;
+    use crate::pay::WechatPay;
+    use crate::sign::{self, PlatformPublicKey};
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::Aes256Gcm;
+
+    fn wechat_pay() -> WechatPay {
+        WechatPay::new(
+            "wxd678efh567hg6787",
+            "1230000109",
+            include_str!("../testdata/apiclient_key.pem"),
+            "5157F09EFDC096DE15EBE81A47057A7232F1B8E1",
+            "0123456789abcdef0123456789abcdef",
+            "https://mydomain.com/combine/notify",
+        )
+    }
+
+    #[test]
+    fn test_combine_pay_params() {
+        let mut params = CombinePayParams::builder()
+            .combine_out_trade_no("P20150806125346")
+            .sub_orders(vec![CombineSubOrder::new(
+                "1230000109",
+                "1900000109",
+                "20150806125346",
+                "腾讯充值中心-QQ会员充值",
+                Money::from_cents(10),
+            )
+            .with_profit_sharing()])
+            .build()
+            .unwrap();
+        params.fill_defaults(&wechat_pay());
+        let json: serde_json::Value = serde_json::from_str(&params.to_json()).unwrap();
+        assert_eq!(json["combine_appid"], "wxd678efh567hg6787");
+        assert_eq!(json["combine_mchid"], "1230000109");
+        assert_eq!(json["sub_orders"][0]["amount"]["total_amount"], 10);
+        assert_eq!(json["sub_orders"][0]["amount"]["currency"], "CNY");
+        assert_eq!(json["sub_orders"][0]["settle_info"]["profit_sharing"], true);
+
+        let err = CombinePayParams::builder()
+            .combine_out_trade_no("P20150806125346")
+            .sub_orders(vec![])
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("sub_orders"));
+    }
+
+    #[test]
+    fn test_profit_sharing_encrypt() {
+        let key =
+            PlatformPublicKey::from_certificate(include_str!("../testdata/apiclient_cert.pem"))
+                .unwrap();
+        let params = EcommerceProfitSharingParams::builder()
+            .sub_mchid("1900000109")
+            .transaction_id("4208450740201411110007820472")
+            .out_order_no("P20150806125346")
+            .receivers(vec![EcommerceProfitSharingReceiver {
+                receiver_type: "PERSONAL_OPENID".to_string(),
+                receiver_account: "oUpF8uMuAJO_M2pxb1Q9zNjWeS6o".to_string(),
+                receiver_name: Some("张三".to_string()),
+                amount: Money::from_cents(100),
+                description: "分给用户".to_string(),
+            }])
+            .finish(true)
+            .build()
+            .unwrap();
+        assert!(params.has_receiver_name());
+        let params = params.encrypt(&key).unwrap();
+        let name = params.receivers[0].receiver_name.as_deref().unwrap();
+        assert_eq!(
+            sign::rsa_oaep_decrypt(include_str!("../testdata/apiclient_key.pem"), name).unwrap(),
+            "张三"
+        );
+    }
+
+    #[test]
+    fn test_refund_params() {
+        let builder = EcommerceRefundParams::builder()
+            .sub_mchid("1900000109")
+            .out_refund_no("1217752501201407033233368018")
+            .amount(EcommerceRefundAmount::new(
+                Money::from_cents(100),
+                Money::from_cents(100),
+            ));
+        let err = builder.clone().build().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("transaction_id or out_trade_no is required"));
+        let mut params = builder
+            .out_trade_no("1217752501201407033233368018")
+            .build()
+            .unwrap();
+        params.out_trade_no = None;
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_decrypt_bill() {
+        use rsa::pkcs8::DecodePublicKey;
+        let aes_key = "fedcba9876543210fedcba9876543210";
+        let nonce = "c21c0e0ff7b1";
+        let bill = "记账时间,微信支付业务单号,资金流水单号\n".as_bytes();
+        let public_key = rsa::RsaPublicKey::from_public_key_pem(
+            &crate::util::x509_to_pem(include_bytes!("../testdata/apiclient_cert.pem")).unwrap(),
+        )
+        .unwrap();
+        let encrypt_key = public_key
+            .encrypt(
+                &mut rsa::rand_core::OsRng,
+                rsa::Oaep::new::<sha1::Sha1>(),
+                aes_key.as_bytes(),
+            )
+            .unwrap();
+        let content = Aes256Gcm::new(aes_key.as_bytes().into())
+            .encrypt(nonce.as_bytes().into(), bill)
+            .unwrap();
+        let hash_value = crate::sign::sha1_hex(bill);
+        let encrypted = EncryptedBill {
+            bill_sequence: Some(1),
+            download_url: "https://api.mch.weixin.qq.com/v3/billdownload/file?token=xxx"
+                .to_string(),
+            encrypt_key: crate::util::base64_encode(encrypt_key),
+            hash_type: Some("SHA1".to_string()),
+            hash_value: Some(hash_value),
+            nonce: nonce.to_string(),
+        };
+        assert_eq!(encrypted.decrypt(&wechat_pay(), &content).unwrap(), bill);
+        assert!(encrypted.decrypt(&wechat_pay(), b"tampered").is_err());
+    }
+}
@@ -14,4 +14,60 @@ pub enum PayError {
     VerifyError(String),
     #[error("weixin not found error")]
     WeixinNotFound,
+    #[error("h5_url referer rejected, check the H5 authorized domain: {0}")]
+    WeixinRefererRejected(String),
+    #[error("h5_url expired: {0}")]
+    WeixinUrlExpired(String),
+    #[error("bill error: {0}")]
+    BillError(String),
+    #[error("money error: {0}")]
+    MoneyError(String),
+    #[error("merchant not found: {0}")]
+    MerchantNotFound(String),
+    #[error("certificate error: {0}")]
+    CertificateError(String),
+    #[error("xml error: {0}")]
+    XmlError(String),
+    #[error("reverse order {out_trade_no} failed: {source}")]
+    ReverseFailed {
+        out_trade_no: String,
+        source: Box<PayError>,
+    },
+    #[error("qrcode error: {0}")]
+    QrCodeError(String),
+    #[error("validation error: {}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; "))]
+    Validation(Vec<ValidationError>),
+}
+
+/// 参数校验失败的字段及原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// 加载商户配置失败
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("read config file {path} error: {message}")]
+    Io { path: String, message: String },
+    #[error("parse config error: {0}")]
+    Parse(String),
+    #[error("invalid config: {}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; "))]
+    Invalid(Vec<ValidationError>),
 }
@@ -0,0 +1,534 @@
+//! 代金券营销，批次创建、激活、暂停、查询，发放代金券，下载批次核销和退款明细
+use crate::datetime;
+use crate::error::PayError;
+use crate::macros::params_builder;
+use crate::money::Money;
+use crate::response::ResponseTrait;
+use crate::sign;
+use crate::validation;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// 批次发放规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StockUseRule {
+    ///【发放总上限】 最多发放的代金券张数
+    pub max_coupons: u64,
+    ///【总预算】 单位为分，等于面额乘以发放总上限
+    pub max_amount: Money,
+    ///【单天预算发放上限】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_amount_by_day: Option<Money>,
+    ///【单个用户可领个数】
+    pub max_coupons_per_user: u32,
+    ///【是否开启自然人限制】
+    pub natural_person_limit: bool,
+    ///【是否开启防刷拦截】
+    pub prevent_api_abuse: bool,
+}
+
+/// 样式信息
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PatternInfo {
+    ///【使用说明】
+    pub description: String,
+    ///【商户logo】 通过图片上传接口获取的url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_logo: Option<String>,
+    ///【品牌名称】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_name: Option<String>,
+    ///【背景颜色】 例如COLOR020
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    ///【券详情图片】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_image: Option<String>,
+}
+
+/// 固定面额满减券
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FixedNormalCoupon {
+    ///【面额】 单位为分
+    pub coupon_amount: Money,
+    ///【门槛】 消费满多少金额可用，单位为分
+    pub transaction_minimum: Money,
+}
+
+/// 核销规则
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CouponUseRule {
+    ///【固定面额满减券使用规则】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_normal_coupon: Option<FixedNormalCoupon>,
+    ///【订单优惠标记】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_tag: Option<Vec<String>>,
+    ///【支付方式】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_type: Option<Vec<String>>,
+    ///【是否可叠加其他优惠】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine_use: Option<bool>,
+    ///【可核销商品编码】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_items: Option<Vec<String>>,
+    ///【可用商户】 可核销代金券的商户号
+    #[serde(default)]
+    pub available_merchants: Vec<String>,
+}
+
+/// 创建代金券批次
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateStockParams {
+    ///【批次名称】 不超过9个字符
+    pub stock_name: String,
+    ///【批次备注】 仅制券商户可见
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    ///【归属商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub belong_merchant: Option<String>,
+    ///【可用时间-开始时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub available_begin_time: DateTime<FixedOffset>,
+    ///【可用时间-结束时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub available_end_time: DateTime<FixedOffset>,
+    ///【发放规则】
+    pub stock_use_rule: StockUseRule,
+    ///【样式设置】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern_info: Option<PatternInfo>,
+    ///【核销规则】
+    pub coupon_use_rule: CouponUseRule,
+    ///【营销经费】 true为免充值，false为预充值
+    pub no_cash: bool,
+    ///【批次类型】 固定为NORMAL
+    pub stock_type: String,
+    ///【商户单据号】 商户创建批次凭据号，商户侧需保持唯一性
+    pub out_request_no: String,
+}
+
+params_builder! {
+    CreateStockParamsBuilder => CreateStockParams {
+        required {
+            stock_name: String => validation::stock_name,
+            available_begin_time: DateTime<FixedOffset>,
+            available_end_time: DateTime<FixedOffset>,
+            stock_use_rule: StockUseRule,
+            coupon_use_rule: CouponUseRule,
+            no_cash: bool,
+            stock_type: String,
+            out_request_no: String => validation::out_bill_no,
+        }
+        optional {
+            comment: String,
+            belong_merchant: String,
+            pattern_info: PatternInfo,
+        }
+        defaults {
+            belong_merchant => mch_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStockResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【创建时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub create_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for CreateStockResponse {}
+
+/// 激活或暂停批次
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StockCreatorParams {
+    ///【创建批次的商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_creator_mchid: Option<String>,
+}
+
+params_builder! {
+    StockCreatorParamsBuilder => StockCreatorParams {
+        required {}
+        optional {
+            stock_creator_mchid: String,
+        }
+        defaults {
+            stock_creator_mchid => mch_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StockStateResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【生效时间】 激活批次时返回
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub start_time: Option<DateTime<FixedOffset>>,
+    ///【暂停时间】 暂停批次时返回
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub pause_time: Option<DateTime<FixedOffset>>,
+}
+
+impl ResponseTrait for StockStateResponse {}
+
+/// 批次详情
+#[derive(Debug, Deserialize)]
+pub struct StockDetail {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【创建批次的商户号】
+    pub stock_creator_mchid: Option<String>,
+    ///【批次名称】
+    pub stock_name: Option<String>,
+    ///【批次状态】 unactivated、audit、running、stoped、paused
+    pub status: Option<String>,
+    ///【创建时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub create_time: Option<DateTime<FixedOffset>>,
+    ///【使用说明】
+    pub description: Option<String>,
+    ///【满减券批次使用规则】
+    pub stock_use_rule: Option<StockUseRule>,
+    ///【可用开始时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub available_begin_time: Option<DateTime<FixedOffset>>,
+    ///【可用结束时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub available_end_time: Option<DateTime<FixedOffset>>,
+    ///【已发券数量】
+    pub distributed_coupons: Option<u64>,
+    ///【是否无资金流】
+    pub no_cash: Option<bool>,
+    ///【批次类型】
+    pub stock_type: Option<String>,
+}
+
+impl ResponseTrait for StockDetail {}
+
+/// 发放代金券
+#[derive(Serialize, Debug, Clone)]
+pub struct SendCouponParams {
+    ///【批次号】
+    pub stock_id: String,
+    ///【商户单据号】 商户此次发放凭据号，同一商户号下唯一
+    pub out_request_no: String,
+    ///【公众账号ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【创建批次的商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_creator_mchid: Option<String>,
+    ///【指定面额发券，面额】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_value: Option<Money>,
+    ///【指定面额发券，券门槛】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon_minimum: Option<Money>,
+}
+
+params_builder! {
+    SendCouponParamsBuilder => SendCouponParams {
+        required {
+            stock_id: String,
+            out_request_no: String => validation::out_bill_no,
+        }
+        optional {
+            appid: String,
+            stock_creator_mchid: String,
+            coupon_value: Money => validation::money,
+            coupon_minimum: Money,
+        }
+        defaults {
+            appid => appid,
+            stock_creator_mchid => mch_id,
+        }
+    }
+}
+
+impl SendCouponParams {
+    pub fn new<S: AsRef<str>>(stock_id: S, out_request_no: S) -> Self {
+        Self {
+            stock_id: stock_id.as_ref().to_string(),
+            out_request_no: out_request_no.as_ref().to_string(),
+            appid: None,
+            stock_creator_mchid: None,
+            coupon_value: None,
+            coupon_minimum: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendCouponResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【代金券id】
+    pub coupon_id: Option<String>,
+}
+
+impl ResponseTrait for SendCouponResponse {}
+
+/// 查询用户代金券，appid不设置时使用WechatPay配置的appid
+#[derive(Debug, Clone, Default)]
+pub struct UserCouponsParams {
+    pub appid: Option<String>,
+    ///【批次号】
+    pub stock_id: Option<String>,
+    ///【券状态】 SENDED（可用）、USED（已实扣）
+    pub status: Option<String>,
+    ///【创建批次的商户号】
+    pub creator_mchid: Option<String>,
+    ///【批次发放商户号】
+    pub sender_mchid: Option<String>,
+    ///【可用商户号】
+    pub available_mchid: Option<String>,
+    ///【分页页码】
+    pub offset: Option<u32>,
+    ///【分页大小】 最大10
+    pub limit: Option<u32>,
+}
+
+impl UserCouponsParams {
+    pub(crate) fn to_query(&self, default_appid: &str) -> String {
+        let mut query = format!("appid={}", self.appid.as_deref().unwrap_or(default_appid));
+        let fields = [
+            ("stock_id", self.stock_id.clone()),
+            ("status", self.status.clone()),
+            ("creator_mchid", self.creator_mchid.clone()),
+            ("sender_mchid", self.sender_mchid.clone()),
+            ("available_mchid", self.available_mchid.clone()),
+            ("offset", self.offset.map(|offset| offset.to_string())),
+            ("limit", self.limit.map(|limit| limit.to_string())),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                query.push_str(&format!("&{}={}", name, value));
+            }
+        }
+        query
+    }
+}
+
+/// 满减券信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct NormalCouponInformation {
+    ///【面额】
+    pub coupon_amount: Money,
+    ///【门槛】
+    pub transaction_minimum: Money,
+}
+
+/// 用户的代金券
+#[derive(Debug, Clone, Deserialize)]
+pub struct Coupon {
+    ///【创建批次的商户号】
+    pub stock_creator_mchid: String,
+    ///【批次号】
+    pub stock_id: String,
+    ///【代金券id】
+    pub coupon_id: String,
+    ///【代金券名称】
+    pub coupon_name: Option<String>,
+    ///【代金券状态】 SENDED、USED、EXPIRED
+    pub status: String,
+    ///【使用说明】
+    pub description: Option<String>,
+    ///【领券时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub create_time: Option<DateTime<FixedOffset>>,
+    ///【券类型】 NORMAL、CUT_TO
+    pub coupon_type: Option<String>,
+    ///【是否无资金流】
+    pub no_cash: Option<bool>,
+    ///【可用开始时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub available_begin_time: Option<DateTime<FixedOffset>>,
+    ///【可用结束时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub available_end_time: Option<DateTime<FixedOffset>>,
+    ///【是否单品优惠】
+    pub singleitem: Option<bool>,
+    ///【满减券信息】
+    pub normal_coupon_information: Option<NormalCouponInformation>,
+    ///【已实扣代金券核销信息】
+    pub consume_information: Option<ConsumeInformation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserCouponsResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【结果集】
+    #[serde(default)]
+    pub data: Vec<Coupon>,
+    ///【查询结果总数】
+    pub total_count: Option<u32>,
+    ///【分页大小】
+    pub limit: Option<u32>,
+    ///【分页页码】
+    pub offset: Option<u32>,
+}
+
+impl ResponseTrait for UserCouponsResponse {}
+
+/// 核销明细或退款明细文件的下载信息
+#[derive(Debug, Deserialize)]
+pub struct FlowDownloadResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【下载链接】 有效期5分钟，需要签名后下载
+    pub url: Option<String>,
+    ///【文件内容的哈希值】
+    pub hash_value: Option<String>,
+    ///【哈希算法类型】 目前只支持SHA1
+    pub hash_type: Option<String>,
+}
+
+impl ResponseTrait for FlowDownloadResponse {}
+
+impl FlowDownloadResponse {
+    /// 校验下载的文件内容
+    pub fn verify(&self, content: &[u8]) -> Result<(), PayError> {
+        let hash_value = self
+            .hash_value
+            .as_deref()
+            .ok_or_else(|| PayError::VerifyError("hash_value is missing".to_string()))?;
+        let actual = sign::sha1_hex(content);
+        if !actual.eq_ignore_ascii_case(hash_value) {
+            return Err(PayError::VerifyError(format!(
+                "flow file hash mismatch, expected {} got {}",
+                hash_value, actual
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 设置消息通知地址
+#[derive(Serialize, Debug, Clone)]
+pub struct FavorCallbackParams {
+    ///【商户号】 不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
+    ///【通知地址】
+    pub notify_url: String,
+    ///【回调开关】 true为开启，false为关闭
+    #[serde(rename = "switch", skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+params_builder! {
+    FavorCallbackParamsBuilder => FavorCallbackParams {
+        required {
+            notify_url: String => validation::notify_url,
+        }
+        optional {
+            mchid: String,
+            enabled: bool,
+        }
+        defaults {
+            mchid => mch_id,
+        }
+    }
+}
+
+impl FavorCallbackParams {
+    pub fn new<S: AsRef<str>>(notify_url: S) -> Self {
+        Self {
+            mchid: None,
+            notify_url: notify_url.as_ref().to_string(),
+            enabled: Some(true),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FavorCallbackResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【修改时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub update_time: Option<DateTime<FixedOffset>>,
+    ///【通知地址】
+    pub notify_url: Option<String>,
+}
+
+impl ResponseTrait for FavorCallbackResponse {}
+
+/// 核销信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsumeInformation {
+    ///【核销时间】
+    #[serde(with = "datetime::rfc3339")]
+    pub consume_time: DateTime<FixedOffset>,
+    ///【核销商户号】
+    pub consume_mchid: String,
+    ///【支付单号】
+    pub transaction_id: String,
+}
+
+/// 代金券核销通知解密后的内容，通知的event_type为COUPON.USE
+pub type CouponUseNotifyData = Coupon;
+
+#[cfg(test)]
+mod tests {
+    use crate::favor::{CouponUseNotifyData, FlowDownloadResponse, UserCouponsParams};
+
+    #[test]
+    fn test_coupon_use_notify() {
+        let data: CouponUseNotifyData = serde_json::from_str(
+            r#"{"stock_creator_mchid":"9800064","stock_id":"9865888","coupon_id":"98674556",
+            "coupon_name":"微信支付代金券","status":"USED","description":"微信支付营销",
+            "create_time":"2015-05-20T13:29:35+08:00","coupon_type":"NORMAL","no_cash":true,
+            "available_begin_time":"2015-05-20T13:29:35+08:00","available_end_time":"2015-05-20T13:29:35+08:00",
+            "singleitem":false,"normal_coupon_information":{"coupon_amount":100,"transaction_minimum":100},
+            "consume_information":{"consume_time":"2015-05-20T13:29:35+08:00","consume_mchid":"9856081",
+            "transaction_id":"4200000404201909069117582536"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            data.normal_coupon_information
+                .unwrap()
+                .coupon_amount
+                .cents(),
+            100
+        );
+        assert_eq!(data.consume_information.unwrap().consume_mchid, "9856081");
+
+        let params = UserCouponsParams {
+            stock_id: Some("9865888".to_string()),
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            params.to_query("wx233544546545989"),
+            "appid=wx233544546545989&stock_id=9865888&limit=10"
+        );
+    }
+
+    #[test]
+    fn test_flow_hash() {
+        let response = FlowDownloadResponse {
+            code: None,
+            message: None,
+            url: None,
+            hash_value: Some("A9993E364706816ABA3E25717850C26C9CD0D89D".to_string()),
+            hash_type: Some("SHA1".to_string()),
+        };
+        assert!(response.verify(b"abc").is_ok());
+        assert!(response.verify(b"abd").is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! 从H5支付的中间页（h5_url）中提取拉起微信客户端的`weixin://`链接
+use crate::error::PayError;
+
+/// 中间页提示referer与H5支付授权域名不一致时出现的内容
+const REFERER_HINTS: &[&str] = &["商家参数格式有误", "referer"];
+
+/// 中间页提示h5_url已过期（有效期5分钟）时出现的内容
+const EXPIRED_HINTS: &[&str] = &["已过期", "已失效", "expired"];
+
+/// 解析中间页，依次查找`deeplink`变量、`<a href>`和任意引号中的`weixin://`链接。
+/// 找不到链接时根据页面提示区分referer被拒绝和链接过期
+pub fn extract_weixin_url(body: &str) -> Result<String, PayError> {
+    let body = unescape(body);
+    if let Some(url) = assigned_value(&body, "deeplink")
+        .or_else(|| href_value(&body))
+        .or_else(|| quoted_value(&body))
+    {
+        return Ok(url.to_string());
+    }
+    Err(page_error(&body))
+}
+
+/// 中间页返回非2xx状态或没有链接时的错误
+pub(crate) fn page_error(body: &str) -> PayError {
+    let lower = body.to_lowercase();
+    if let Some(hint) = REFERER_HINTS.iter().find(|hint| lower.contains(*hint)) {
+        return PayError::WeixinRefererRejected(hint.to_string());
+    }
+    if let Some(hint) = EXPIRED_HINTS.iter().find(|hint| lower.contains(*hint)) {
+        return PayError::WeixinUrlExpired(hint.to_string());
+    }
+    PayError::WeixinNotFound
+}
+
+/// `name = "..."`或`name: '...'`形式的赋值
+fn assigned_value<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    body.match_indices(name).find_map(|(index, _)| {
+        let rest = body[index + name.len()..].trim_start();
+        let rest = rest.strip_prefix(['=', ':'])?;
+        quoted_prefix(rest.trim_start()).filter(|value| value.starts_with("weixin://"))
+    })
+}
+
+/// `href="weixin://..."`形式的链接
+fn href_value(body: &str) -> Option<&str> {
+    assigned_value(body, "href")
+}
+
+/// 任意引号中的`weixin://`链接
+fn quoted_value(body: &str) -> Option<&str> {
+    body.match_indices("weixin://").find_map(|(index, _)| {
+        let quote = body[..index].chars().next_back()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let end = body[index..].find(quote)?;
+        Some(&body[index..index + end])
+    })
+}
+
+/// 读取以引号开头的字符串内容
+fn quoted_prefix(text: &str) -> Option<&str> {
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let text = &text[1..];
+    let end = text.find(quote)?;
+    Some(&text[..end])
+}
+
+/// 还原HTML实体和JS转义
+fn unescape(body: &str) -> String {
+    body.replace("&amp;", "&")
+        .replace("\\u0026", "&")
+        .replace("\\x26", "&")
+        .replace("\\/", "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PayError;
+    use crate::h5::extract_weixin_url;
+
+    const EXPECTED: &str = "weixin://wap/pay?prepayid%3Dwx13013716281xa5df8313490000&package=35748946&noncestr=1705081036&sign=8d988c82ded5fb02f097d6f1d70";
+
+    #[test]
+    fn test_extract_weixin_url() {
+        let deeplink = include_str!("../testdata/h5/deeplink.html");
+        assert_eq!(extract_weixin_url(deeplink).unwrap(), EXPECTED);
+        let anchor = include_str!("../testdata/h5/anchor.html");
+        assert_eq!(extract_weixin_url(anchor).unwrap(), EXPECTED);
+        let escaped = r#"<script>window.location.replace('weixin:\/\/wap\/pay?prepayid%3Dwx1&package=1');</script>"#;
+        assert_eq!(
+            extract_weixin_url(escaped).unwrap(),
+            "weixin://wap/pay?prepayid%3Dwx1&package=1"
+        );
+    }
+
+    #[test]
+    fn test_page_error() {
+        let referer = include_str!("../testdata/h5/referer.html");
+        assert!(matches!(
+            extract_weixin_url(referer),
+            Err(PayError::WeixinRefererRejected(_))
+        ));
+        let expired = include_str!("../testdata/h5/expired.html");
+        assert!(matches!(
+            extract_weixin_url(expired),
+            Err(PayError::WeixinUrlExpired(_))
+        ));
+        assert!(matches!(
+            extract_weixin_url("<html></html>"),
+            Err(PayError::WeixinNotFound)
+        ));
+    }
+}
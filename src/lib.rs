@@ -3,12 +3,35 @@
 pub mod blocking;
 #[cfg(not(feature = "blocking"))]
 pub mod async_impl;
+pub mod applyment;
+pub mod bill;
+pub mod busifavor;
+pub mod complaint;
+pub mod config;
+pub mod datetime;
+pub mod media;
+pub mod ecommerce;
 pub mod error;
+pub mod h5;
+pub mod favor;
 pub mod model;
+pub mod money;
+pub mod papay;
 pub mod pay;
 pub mod pay_type;
+pub mod payscore;
+#[cfg(feature = "qrcode")]
+pub mod qrcode;
+pub mod reconcile;
+pub mod registry;
 pub mod request;
 pub mod response;
+pub mod secret;
+pub mod tls;
 pub mod sign;
 pub mod util;
+pub mod v2;
 pub(crate) mod macros;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod poll;
+pub(crate) mod validation;
@@ -4,7 +4,7 @@ macro_rules! error {
     ($name:expr)=>{
         tracing::error!($name);
     };
-   ($name:expr $(,$arg:tt)+) => {
+   ($name:expr $(,$arg:expr)+ $(,)?) => {
        tracing::error!($name, $($arg),*);
     };
 }
@@ -12,7 +12,7 @@ macro_rules! error {
 #[macro_export]
 macro_rules! error {
     ($name:expr)=>{};
-    ($name:expr $(,$arg:tt)+ $(,)?) => {};
+    ($name:expr $(,$arg:expr)+ $(,)?) => {};
 }
 
 #[cfg(feature = "debug-print")]
@@ -21,7 +21,7 @@ macro_rules! debug {
     ($name:expr)=>{
         tracing::debug!($name);
     };
-    ($name:expr $(,$arg:tt)+) => {
+    ($name:expr $(,$arg:expr)+ $(,)?) => {
         tracing::debug!($name, $($arg),*);
     };
 }
@@ -30,5 +30,132 @@ macro_rules! debug {
 #[macro_export]
 macro_rules! debug {
     ($name:expr)=>{};
-    ($name:expr $(,$arg:tt)+ $(,)?) => {};
+    ($name:expr $(,$arg:expr)+ $(,)?) => {};
 }
+
+/// 为请求参数生成构建器，`required`中的字段缺失时报错，`=>`后为该字段的校验规则。
+/// 同时生成`ParamsTrait`实现，`validate`与构建时使用相同的校验规则，
+/// `defaults`中的字段未设置时由`WechatPay`的同名配置补全。
+/// 可选的`check`为涉及多个字段的校验，在各字段校验通过后执行。
+macro_rules! params_builder {
+    (
+        $builder:ident => $params:ident {
+            required {
+                $($req:ident: $req_ty:ty $(=> $req_rule:path)?),* $(,)?
+            }
+            optional {
+                $($opt:ident: $opt_ty:ty $(=> $opt_rule:path)?),* $(,)?
+            }
+            defaults {
+                $($def:ident => $source:ident),* $(,)?
+            }
+            $(check { $check:path })?
+        }
+    ) => {
+        #[doc = concat!("[`", stringify!($params), "`]的构建器")]
+        #[derive(Debug, Clone, Default)]
+        pub struct $builder {
+            $($req: Option<$req_ty>,)*
+            $($opt: Option<$opt_ty>,)*
+        }
+
+        impl $builder {
+            $(
+                pub fn $req(mut self, value: impl Into<$req_ty>) -> Self {
+                    self.$req = Some(value.into());
+                    self
+                }
+            )*
+            $(
+                pub fn $opt(mut self, value: impl Into<$opt_ty>) -> Self {
+                    self.$opt = Some(value.into());
+                    self
+                }
+            )*
+
+            /// 构建参数，缺失必填字段或校验失败时返回所有出错的字段
+            pub fn build(self) -> Result<$params, $crate::error::PayError> {
+                #[allow(unused_mut)]
+                let mut errors: Vec<$crate::error::ValidationError> = Vec::new();
+                $(
+                    match &self.$req {
+                        #[allow(unused_variables)]
+                        Some(value) => {
+                            $($req_rule(stringify!($req), value, &mut errors);)?
+                        }
+                        None => errors.push($crate::error::ValidationError::new(
+                            stringify!($req),
+                            "is required",
+                        )),
+                    }
+                )*
+                $(
+                    $(
+                        if let Some(value) = &self.$opt {
+                            $opt_rule(stringify!($opt), value, &mut errors);
+                        }
+                    )?
+                )*
+                if !errors.is_empty() {
+                    return Err($crate::error::PayError::Validation(errors));
+                }
+                let params = $params {
+                    $($req: self.$req.unwrap(),)*
+                    $($opt: self.$opt,)*
+                };
+                $(
+                    $check(&params, &mut errors);
+                    if !errors.is_empty() {
+                        return Err($crate::error::PayError::Validation(errors));
+                    }
+                )?
+                Ok(params)
+            }
+        }
+
+        impl $params {
+            pub fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        impl $crate::model::ParamsTrait for $params {
+            fn to_json(&self) -> String {
+                serde_json::to_string(self).unwrap()
+            }
+
+            #[allow(unused_variables)]
+            fn fill_defaults(&mut self, pay: &$crate::pay::WechatPay) {
+                $(
+                    if self.$def.is_none() {
+                        let value = $crate::pay::WechatPayTrait::$source(pay);
+                        if !value.is_empty() {
+                            self.$def = Some(value);
+                        }
+                    }
+                )*
+            }
+
+            fn validate(&self) -> Result<(), $crate::error::PayError> {
+                #[allow(unused_mut)]
+                let mut errors: Vec<$crate::error::ValidationError> = Vec::new();
+                $($($req_rule(stringify!($req), &self.$req, &mut errors);)?)*
+                $(
+                    $(
+                        if let Some(value) = &self.$opt {
+                            $opt_rule(stringify!($opt), value, &mut errors);
+                        }
+                    )?
+                )*
+                $($check(self, &mut errors);)?
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err($crate::error::PayError::Validation(errors))
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use params_builder;
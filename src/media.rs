@@ -0,0 +1,160 @@
+//! 图片、视频上传，返回的media_id用于投诉处理、特约商户进件、营销等接口
+use crate::error::{PayError, ValidationError};
+use crate::sign;
+use serde::Serialize;
+
+/// 上传的媒体文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    ///图片，支持JPG、BMP、PNG，不超过2M
+    Image,
+    ///视频，支持avi、wmv、mpeg、mp4、mov、mkv、flv、f4v、m4v、rmvb，不超过5M
+    Video,
+}
+
+impl MediaType {
+    pub fn url(&self) -> &str {
+        match self {
+            MediaType::Image => "/v3/merchant/media/upload",
+            MediaType::Video => "/v3/merchant/media/video_upload",
+        }
+    }
+
+    /// 文件大小上限，单位为字节
+    pub fn max_size(&self) -> usize {
+        match self {
+            MediaType::Image => 2 * 1024 * 1024,
+            MediaType::Video => 5 * 1024 * 1024,
+        }
+    }
+
+    /// 根据扩展名返回文件的Content-Type，不支持的扩展名返回`None`
+    pub fn content_type(&self, extension: &str) -> Option<&'static str> {
+        let extension = extension.to_ascii_lowercase();
+        match (self, extension.as_str()) {
+            (MediaType::Image, "jpg" | "jpeg") => Some("image/jpeg"),
+            (MediaType::Image, "png") => Some("image/png"),
+            (MediaType::Image, "bmp") => Some("image/bmp"),
+            (MediaType::Video, "avi") => Some("video/x-msvideo"),
+            (MediaType::Video, "wmv") => Some("video/x-ms-wmv"),
+            (MediaType::Video, "mpeg") => Some("video/mpeg"),
+            (MediaType::Video, "mp4") => Some("video/mp4"),
+            (MediaType::Video, "mov") => Some("video/quicktime"),
+            (MediaType::Video, "mkv") => Some("video/x-matroska"),
+            (MediaType::Video, "flv") => Some("video/x-flv"),
+            (MediaType::Video, "f4v") => Some("video/x-f4v"),
+            (MediaType::Video, "m4v") => Some("video/x-m4v"),
+            (MediaType::Video, "rmvb") => Some("application/vnd.rn-realmedia-vbr"),
+            _ => None,
+        }
+    }
+}
+
+/// 上传接口的meta部分，签名时使用meta的JSON作为请求主体
+#[derive(Serialize, Debug, Clone)]
+pub struct MediaMeta {
+    ///【文件名称】 必须以图片或视频的扩展名结尾
+    pub filename: String,
+    ///【文件摘要】 文件内容的SHA256摘要，小写十六进制
+    pub sha256: String,
+}
+
+/// 校验通过的待上传文件
+#[derive(Debug, Clone)]
+pub(crate) struct MediaUpload {
+    pub meta: String,
+    pub filename: String,
+    pub content_type: &'static str,
+    pub content: Vec<u8>,
+}
+
+impl MediaUpload {
+    /// 校验文件类型和大小，计算文件摘要
+    pub fn new(media_type: MediaType, filename: &str, content: Vec<u8>) -> Result<Self, PayError> {
+        let mut errors = Vec::new();
+        let extension = filename.rsplit_once('.').map(|(_, extension)| extension);
+        let content_type = extension.and_then(|extension| media_type.content_type(extension));
+        if content_type.is_none() {
+            errors.push(ValidationError::new(
+                "filename",
+                format!("unsupported {:?} file type: {}", media_type, filename),
+            ));
+        }
+        if content.is_empty() {
+            errors.push(ValidationError::new("file", "must not be empty"));
+        } else if content.len() > media_type.max_size() {
+            errors.push(ValidationError::new(
+                "file",
+                format!(
+                    "must be at most {} bytes, got {}",
+                    media_type.max_size(),
+                    content.len()
+                ),
+            ));
+        }
+        if let Some(expected) = content_type.filter(|_| media_type == MediaType::Image) {
+            if image_content_type(&content) != Some(expected) {
+                errors.push(ValidationError::new(
+                    "file",
+                    format!("content does not match {}", expected),
+                ));
+            }
+        }
+        let Some(content_type) = content_type.filter(|_| errors.is_empty()) else {
+            return Err(PayError::Validation(errors));
+        };
+        let meta = MediaMeta {
+            filename: filename.to_string(),
+            sha256: sign::sha256_hex(&content),
+        };
+        Ok(Self {
+            meta: serde_json::to_string(&meta)?,
+            filename: filename.to_string(),
+            content_type,
+            content,
+        })
+    }
+}
+
+/// 根据文件头识别图片格式
+fn image_content_type(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if content.starts_with(&[0x89, b'P', b'N', b'G']) {
+        Some("image/png")
+    } else if content.starts_with(b"BM") {
+        Some("image/bmp")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PayError;
+    use crate::media::{MediaType, MediaUpload};
+
+    #[test]
+    fn test_media_upload() {
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A].to_vec();
+        let upload = MediaUpload::new(MediaType::Image, "qrcode.PNG", png.clone()).unwrap();
+        assert_eq!(upload.content_type, "image/png");
+        assert_eq!(
+            upload.meta,
+            r#"{"filename":"qrcode.PNG","sha256":"4c4b6a3be1314ab86138bef4314dde022e600960d8689a2c8f8631802d20dab6"}"#
+        );
+
+        let error = MediaUpload::new(MediaType::Image, "qrcode.jpg", png).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "validation error: file: content does not match image/jpeg"
+        );
+        let error = MediaUpload::new(MediaType::Video, "demo.gif", vec![0; 5 * 1024 * 1024 + 1])
+            .unwrap_err();
+        let fields = match error {
+            PayError::Validation(errors) => errors.into_iter().map(|e| e.field).collect::<Vec<_>>(),
+            other => panic!("unexpected error: {}", other),
+        };
+        assert_eq!(fields, vec!["filename", "file"]);
+    }
+}
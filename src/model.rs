@@ -1,19 +1,91 @@
+use crate::datetime;
+use crate::error::{PayError, ValidationError};
+use crate::macros::params_builder;
+use crate::money::Money;
+use crate::pay::WechatPay;
+use crate::pay_type::{BankType, PayType, TradeState};
+use crate::validation;
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 pub trait ParamsTrait {
     fn to_json(&self) -> String;
+
+    /// 使用商户配置补全未设置的appid、mchid和notify_url，默认不做处理
+    fn fill_defaults(&mut self, _pay: &WechatPay) {}
+
+    /// 发送请求前校验参数，默认不做校验
+    fn validate(&self) -> Result<(), PayError> {
+        Ok(())
+    }
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Currency {
+    ///人民币
+    #[default]
     CNY,
+    ///港币
+    HKD,
+    ///美元
+    USD,
+    ///欧元
+    EUR,
+    ///英镑
+    GBP,
+    ///日元
+    JPY,
+    ///韩元
+    KRW,
+    ///澳大利亚元
+    AUD,
+    ///加拿大元
+    CAD,
+    ///新西兰元
+    NZD,
+    ///新加坡元
+    SGD,
+    ///瑞士法郎
+    CHF,
+    ///泰铢
+    THB,
+    ///澳门元
+    MOP,
+    ///未列出的币种，反序列化时不会因新币种失败，但无法还原原始的币种代码
+    #[serde(other)]
+    Unknown,
 }
 
 impl Display for Currency {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Currency::CNY => write!(f, "CNY"),
+            Currency::HKD => write!(f, "HKD"),
+            Currency::USD => write!(f, "USD"),
+            Currency::EUR => write!(f, "EUR"),
+            Currency::GBP => write!(f, "GBP"),
+            Currency::JPY => write!(f, "JPY"),
+            Currency::KRW => write!(f, "KRW"),
+            Currency::AUD => write!(f, "AUD"),
+            Currency::CAD => write!(f, "CAD"),
+            Currency::NZD => write!(f, "NZD"),
+            Currency::SGD => write!(f, "SGD"),
+            Currency::CHF => write!(f, "CHF"),
+            Currency::THB => write!(f, "THB"),
+            Currency::MOP => write!(f, "MOP"),
+            Currency::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+impl Currency {
+    /// 最小货币单位相对主货币单位的小数位数，日元、韩元没有辅币单位为0，其余为2
+    pub fn minor_unit_exponent(&self) -> u32 {
+        match self {
+            Currency::JPY | Currency::KRW => 0,
+            _ => 2,
         }
     }
 }
@@ -23,14 +95,60 @@ unsafe impl Send for Currency {}
 unsafe impl Sync for Currency {}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "AmountInfoRepr", into = "AmountInfoRepr")]
 pub struct AmountInfo {
-    ///【标价金额】 订单总金额，单位为分。
-    pub total: i32,
+    ///【标价金额】 订单总金额，单位为分，币种随金额一起保存，序列化时输出到`currency`字段。
+    pub total: Money,
+    ///【用户支付金额】 用户实际支付金额，只在查询订单和支付通知中返回，币种输出到`payer_currency`字段。
+    pub payer_total: Option<Money>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountInfoRepr {
+    total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payer_total: Option<i64>,
+    #[serde(default)]
+    currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payer_currency: Option<Currency>,
+}
+
+impl From<AmountInfoRepr> for AmountInfo {
+    fn from(value: AmountInfoRepr) -> Self {
+        let payer_currency = value.payer_currency.unwrap_or(value.currency);
+        Self {
+            total: Money::new(value.total, value.currency),
+            payer_total: value
+                .payer_total
+                .map(|payer_total| Money::new(payer_total, payer_currency)),
+        }
+    }
+}
+
+impl From<AmountInfo> for AmountInfoRepr {
+    fn from(value: AmountInfo) -> Self {
+        Self {
+            total: value.total.cents(),
+            payer_total: value.payer_total.map(|payer_total| payer_total.cents()),
+            currency: value.total.currency(),
+            payer_currency: value.payer_total.map(|payer_total| payer_total.currency()),
+        }
+    }
 }
 
 impl From<i32> for AmountInfo {
     fn from(value: i32) -> Self {
-        Self { total: value }
+        Money::from(value).into()
+    }
+}
+
+impl From<Money> for AmountInfo {
+    fn from(value: Money) -> Self {
+        Self {
+            total: value,
+            payer_total: None,
+        }
     }
 }
 
@@ -63,7 +181,7 @@ pub struct GoodsDetail {
     ///【商品数量】 用户购买的数量
     pub quantity: i32,
     ///【商品单价】 单位为：分。如果商户有优惠，需传输商户优惠后的单价(例如：用户对一笔100元的订单使用了商场发的纸质优惠券100-50，则活动商品的单价应为原单价-50)
-    pub unit_price: i32,
+    pub unit_price: Money,
     ///【微信支付商品编码】 微信支付定义的统一商品编号（没有可不传）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wechatpay_goods_id: Option<String>,
@@ -83,7 +201,7 @@ pub struct OrderDetail {
     /// 2、当订单原价与支付金额不相等，则不享受优惠。
     /// 3、该字段主要用于防止同一张小票分多次支付，以享受多次优惠的情况，正常支付订单不必上传此参数。
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub cost_price: Option<i32>,
+    pub cost_price: Option<Money>,
     ///【商品小票ID】 商家小票ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub invoice_id: Option<String>,
@@ -201,12 +319,17 @@ impl ParamsTrait for SceneInfo {
 
 #[derive(Serialize, Debug, Clone)]
 pub struct JsapiParams {
-    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。
-    pub appid: String,
-    ///【商户号】 微信支付商户号
-    pub mchid: String,
+    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
     ///【商品描述】 商品描述
     pub description: String,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP。不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
     ///【商户订单号】 商户系统内部订单号，只能是数字、大小写字母_-*且在同一个商户号下唯一。
     pub out_trade_no: String,
     ///【订单金额】 订单金额信息
@@ -221,26 +344,51 @@ pub struct JsapiParams {
     pub detail: Option<OrderDetail>,
     ///【交易结束时间】 订单失效时间，遵循rfc3339标准格式，格式为yyyy-MM-DDTHH:mm:ss+TIMEZONE，yyyy-MM-DD表示年月日，T出现在字符串中，表示time元素的开头，HH:mm:ss表示时分秒，TIMEZONE表示时区（+08:00表示东八区时间，领先UTC8小时，即北京时间）。例如：2015-05-20T13:29:35+08:00表示，北京时间2015年5月20日13点29分35秒。
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub time_expire: Option<String>,
+    #[serde(with = "datetime::rfc3339_option")]
+    pub time_expire: Option<DateTime<FixedOffset>>,
     ///【场景信息】 支付场景描述
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scene_info: Option<SceneInfo>,
 }
 
-impl ParamsTrait for JsapiParams {
-    fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+params_builder! {
+    JsapiParamsBuilder => JsapiParams {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            amount: AmountInfo => validation::amount,
+            payer: PayerInfo,
+        }
+        optional {
+            appid: String,
+            mchid: String,
+            notify_url: String => validation::notify_url,
+            attach: String => validation::attach,
+            detail: OrderDetail,
+            time_expire: DateTime<FixedOffset>,
+            scene_info: SceneInfo,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+            notify_url => notify_url,
+        }
     }
 }
 
 #[derive(Serialize, Debug, Clone)]
 pub struct MicroParams {
-    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。
-    pub appid: String,
-    ///【商户号】 微信支付商户号
-    pub mchid: String,
+    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
     ///【商品描述】 商品描述
     pub description: String,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP。不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
     ///【商户订单号】 商户系统内部订单号，只能是数字、大小写字母_-*且在同一个商户号下唯一。
     pub out_trade_no: String,
     ///【订单金额】 订单金额信息
@@ -255,30 +403,49 @@ pub struct MicroParams {
     pub detail: Option<OrderDetail>,
     ///【交易结束时间】 订单失效时间，遵循rfc3339标准格式，格式为yyyy-MM-DDTHH:mm:ss+TIMEZONE，yyyy-MM-DD表示年月日，T出现在字符串中，表示time元素的开头，HH:mm:ss表示时分秒，TIMEZONE表示时区（+08:00表示东八区时间，领先UTC8小时，即北京时间）。例如：2015-05-20T13:29:35+08:00表示，北京时间2015年5月20日13点29分35秒。
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub time_expire: Option<String>,
+    #[serde(with = "datetime::rfc3339_option")]
+    pub time_expire: Option<DateTime<FixedOffset>>,
     ///【场景信息】 支付场景描述
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scene_info: Option<SceneInfo>,
 }
 
-impl ParamsTrait for MicroParams {
-    fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+params_builder! {
+    MicroParamsBuilder => MicroParams {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            amount: AmountInfo => validation::amount,
+            payer: PayerInfo,
+        }
+        optional {
+            appid: String,
+            mchid: String,
+            notify_url: String => validation::notify_url,
+            attach: String => validation::attach,
+            detail: OrderDetail,
+            time_expire: DateTime<FixedOffset>,
+            scene_info: SceneInfo,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+            notify_url => notify_url,
+        }
     }
 }
 
 impl MicroParams {
     pub fn new<S: AsRef<str>>(
-        appid: S,
-        mchid: S,
         description: S,
         out_trade_no: S,
         amount: AmountInfo,
         payer: PayerInfo,
     ) -> Self {
         Self {
-            appid: appid.as_ref().to_string(),
-            mchid: mchid.as_ref().to_string(),
+            appid: None,
+            mchid: None,
+            notify_url: None,
             description: description.as_ref().to_string(),
             out_trade_no: out_trade_no.as_ref().to_string(),
             amount,
@@ -293,16 +460,15 @@ impl MicroParams {
 
 impl JsapiParams {
     pub fn new<S: AsRef<str>>(
-        appid: S,
-        mchid: S,
         description: S,
         out_trade_no: S,
         amount: AmountInfo,
         payer: PayerInfo,
     ) -> Self {
         Self {
-            appid: appid.as_ref().to_string(),
-            mchid: mchid.as_ref().to_string(),
+            appid: None,
+            mchid: None,
+            notify_url: None,
             description: description.as_ref().to_string(),
             out_trade_no: out_trade_no.as_ref().to_string(),
             amount,
@@ -328,21 +494,25 @@ unsafe impl Sync for SettleInfo {}
 
 #[derive(Serialize, Debug, Clone)]
 pub struct NativeParams {
-    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。
-    pub appid: String,
-    ///【商户号】 微信支付商户号
-    pub mchid: String,
+    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
     ///【商品描述】 商品描述
     pub description: String,
-    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP
-    pub notify_url: String,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP。不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
     ///【商户订单号】 商户系统内部订单号，只能是数字、大小写字母_-*且在同一个商户号下唯一。
     pub out_trade_no: String,
     ///【订单金额】 订单金额信息
     pub amount: AmountInfo,
     ///【交易结束时间】 订单失效时间，遵循rfc3339标准格式，格式为yyyy-MM-DDTHH:mm:ss+TIMEZONE，yyyy-MM-DD表示年月日，T出现在字符串中，表示time元素的开头，HH:mm:ss表示时分秒，TIMEZONE表示时区（+08:00表示东八区时间，领先UTC8小时，即北京时间）。例如：2015-05-20T13:29:35+08:00表示，北京时间2015年5月20日13点29分35秒。
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub time_expire: Option<String>,
+    #[serde(with = "datetime::rfc3339_option")]
+    pub time_expire: Option<DateTime<FixedOffset>>,
     ///【附加数据】 附加数据，在查询API和支付通知中原样返回，可作为自定义参数使用，实际情况下只有支付完成状态才会返回该字段。
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attach: Option<String>,
@@ -360,29 +530,53 @@ pub struct NativeParams {
     pub settle_info: Option<SettleInfo>,
 }
 
-impl ParamsTrait for NativeParams {
-    fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+params_builder! {
+    NativeParamsBuilder => NativeParams {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            amount: AmountInfo => validation::amount,
+        }
+        optional {
+            appid: String,
+            mchid: String,
+            notify_url: String => validation::notify_url,
+            time_expire: DateTime<FixedOffset>,
+            attach: String => validation::attach,
+            goods_tag: String,
+            support_fapiao: bool,
+            scene_info: SceneInfo,
+            settle_info: SettleInfo,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+            notify_url => notify_url,
+        }
     }
 }
 
 #[derive(Serialize, Debug, Clone)]
 pub struct AppParams {
-    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。
-    pub appid: String,
-    ///【商户号】 微信支付商户号
-    pub mchid: String,
+    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
     ///【商品描述】 商品描述
     pub description: String,
-    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP
-    pub notify_url: String,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP。不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
     ///【商户订单号】 商户系统内部订单号，只能是数字、大小写字母_-*且在同一个商户号下唯一。
     pub out_trade_no: String,
     ///【订单金额】 订单金额信息
     pub amount: AmountInfo,
     ///【交易结束时间】 订单失效时间，遵循rfc3339标准格式，格式为yyyy-MM-DDTHH:mm:ss+TIMEZONE，yyyy-MM-DD表示年月日，T出现在字符串中，表示time元素的开头，HH:mm:ss表示时分秒，TIMEZONE表示时区（+08:00表示东八区时间，领先UTC8小时，即北京时间）。例如：2015-05-20T13:29:35+08:00表示，北京时间2015年5月20日13点29分35秒。
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub time_expire: Option<String>,
+    #[serde(with = "datetime::rfc3339_option")]
+    pub time_expire: Option<DateTime<FixedOffset>>,
     ///【附加数据】 附加数据，在查询API和支付通知中原样返回，可作为自定义参数使用，实际情况下只有支付完成状态才会返回该字段。
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attach: Option<String>,
@@ -403,26 +597,44 @@ pub struct AppParams {
     pub settle_info: Option<SettleInfo>,
 }
 
-impl ParamsTrait for AppParams {
-    fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+params_builder! {
+    AppParamsBuilder => AppParams {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            amount: AmountInfo => validation::amount,
+        }
+        optional {
+            appid: String,
+            mchid: String,
+            notify_url: String => validation::notify_url,
+            time_expire: DateTime<FixedOffset>,
+            attach: String => validation::attach,
+            goods_tag: String,
+            support_fapiao: bool,
+            detail: OrderDetail,
+            scene_info: SceneInfo,
+            settle_info: SettleInfo,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+            notify_url => notify_url,
+        }
     }
 }
 
 impl AppParams {
     pub fn new<S: AsRef<str>>(
-        appid: S,
-        mchid: S,
-        notify_url: S,
         description: S,
         out_trade_no: S,
         amount: AmountInfo,
     ) -> Self {
         Self {
-            appid: appid.as_ref().to_string(),
-            mchid: mchid.as_ref().to_string(),
+            appid: None,
+            mchid: None,
             description: description.as_ref().to_string(),
-            notify_url: notify_url.as_ref().to_string(),
+            notify_url: None,
             out_trade_no: out_trade_no.as_ref().to_string(),
             amount,
             time_expire: None,
@@ -438,21 +650,25 @@ impl AppParams {
 
 #[derive(Serialize, Debug, Clone)]
 pub struct H5Params {
-    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。
-    pub appid: String,
-    ///【商户号】 微信支付商户号
-    pub mchid: String,
+    ///【AppID】微信开放平台(移动应用)或微信公众平台(小程序、公众号)为开发者的应用程序提供的唯一标识。不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
     ///【商品描述】 商品描述
     pub description: String,
-    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP
-    pub notify_url: String,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP。不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
     ///【商户订单号】 商户系统内部订单号，只能是数字、大小写字母_-*且在同一个商户号下唯一。
     pub out_trade_no: String,
     ///【订单金额】 订单金额信息
     pub amount: AmountInfo,
     ///【交易结束时间】 订单失效时间，遵循rfc3339标准格式，格式为yyyy-MM-DDTHH:mm:ss+TIMEZONE，yyyy-MM-DD表示年月日，T出现在字符串中，表示time元素的开头，HH:mm:ss表示时分秒，TIMEZONE表示时区（+08:00表示东八区时间，领先UTC8小时，即北京时间）。例如：2015-05-20T13:29:35+08:00表示，北京时间2015年5月20日13点29分35秒。
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub time_expire: Option<String>,
+    #[serde(with = "datetime::rfc3339_option")]
+    pub time_expire: Option<DateTime<FixedOffset>>,
     ///【附加数据】 附加数据，在查询API和支付通知中原样返回，可作为自定义参数使用，实际情况下只有支付完成状态才会返回该字段。
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attach: Option<String>,
@@ -469,26 +685,43 @@ pub struct H5Params {
     pub settle_info: Option<SettleInfo>,
 }
 
-impl ParamsTrait for H5Params {
-    fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+params_builder! {
+    H5ParamsBuilder => H5Params {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            amount: AmountInfo => validation::amount,
+            scene_info: H5SceneInfo,
+        }
+        optional {
+            appid: String,
+            mchid: String,
+            notify_url: String => validation::notify_url,
+            time_expire: DateTime<FixedOffset>,
+            attach: String => validation::attach,
+            goods_tag: String,
+            support_fapiao: bool,
+            settle_info: SettleInfo,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+            notify_url => notify_url,
+        }
     }
 }
 
 impl H5Params {
     pub fn new<S: AsRef<str>>(
-        appid: S,
-        mchid: S,
-        notify_url: S,
         description: S,
         out_trade_no: S,
         amount: AmountInfo,
         scene_info: H5SceneInfo,
     ) -> Self {
         Self {
-            appid: appid.as_ref().to_string(),
-            mchid: mchid.as_ref().to_string(),
-            notify_url: notify_url.as_ref().to_string(),
+            appid: None,
+            mchid: None,
+            notify_url: None,
             description: description.as_ref().to_string(),
             out_trade_no: out_trade_no.as_ref().to_string(),
             amount,
@@ -504,17 +737,14 @@ impl H5Params {
 
 impl NativeParams {
     pub fn new<S: AsRef<str>>(
-        appid: S,
-        mchid: S,
-        notify_url: S,
         description: S,
         out_trade_no: S,
         amount: AmountInfo,
     ) -> Self {
         Self {
-            appid: appid.as_ref().to_string(),
-            mchid: mchid.as_ref().to_string(),
-            notify_url: notify_url.as_ref().to_string(),
+            appid: None,
+            mchid: None,
+            notify_url: None,
             description: description.as_ref().to_string(),
             out_trade_no: out_trade_no.as_ref().to_string(),
             amount,
@@ -528,6 +758,213 @@ impl NativeParams {
     }
 }
 
+/// 关闭订单
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CloseOrderParams {
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
+}
+
+params_builder! {
+    CloseOrderParamsBuilder => CloseOrderParams {
+        required {}
+        optional {
+            mchid: String,
+        }
+        defaults {
+            mchid => mch_id,
+        }
+    }
+}
+
+/// 付款码支付的门店信息
+#[derive(Serialize, Debug, Clone)]
+pub struct CodepayStoreInfo {
+    ///【商户侧门店编号】 商户侧门店编号，总长度不超过32字符
+    pub out_id: String,
+}
+
+/// 付款码支付的场景信息
+#[derive(Serialize, Debug, Clone)]
+pub struct CodepaySceneInfo {
+    ///【商户门店信息】 商户门店信息
+    pub store_info: CodepayStoreInfo,
+    ///【商户端设备IP】 收银设备的IP地址，支持IPv4和IPv6
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_ip: Option<String>,
+}
+
+impl CodepaySceneInfo {
+    pub fn new<S: AsRef<str>>(store_out_id: S) -> Self {
+        Self {
+            store_info: CodepayStoreInfo {
+                out_id: store_out_id.as_ref().to_string(),
+            },
+            device_ip: None,
+        }
+    }
+}
+
+/// 付款码支付的支付者信息
+#[derive(Serialize, Debug, Clone)]
+pub struct CodepayPayer {
+    ///【付款码】 用户付款码，18位纯数字，以10、11、12、13、14、15开头
+    pub auth_code: String,
+}
+
+impl From<&str> for CodepayPayer {
+    fn from(value: &str) -> Self {
+        Self {
+            auth_code: value.to_string(),
+        }
+    }
+}
+
+/// 付款码支付，商户扫描用户微信中的付款码收款。
+/// 与小程序支付`MicroParams`不同，付款码支付同步返回支付结果，没有支付通知
+#[derive(Serialize, Debug, Clone)]
+pub struct CodepayParams {
+    ///【公众账号ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
+    ///【商品描述】 商品描述
+    pub description: String,
+    ///【商户订单号】 商户系统内部订单号，只能是数字、大小写字母_-*且在同一个商户号下唯一。
+    pub out_trade_no: String,
+    ///【订单金额】 订单金额信息
+    pub amount: AmountInfo,
+    ///【场景信息】 门店和收银设备信息
+    pub scene_info: CodepaySceneInfo,
+    ///【支付者】 用户付款码
+    pub payer: CodepayPayer,
+    ///【附加数据】 附加数据，在查询API中原样返回，可作为自定义参数使用。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+    ///【订单优惠标记】 商品标记，代金券或立减优惠功能的参数。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_tag: Option<String>,
+    ///【电子发票入口开放标识】 传入true时，支付成功消息和支付详情页将出现开票入口。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub support_fapiao: Option<bool>,
+    ///【结算信息】 结算信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settle_info: Option<SettleInfo>,
+}
+
+params_builder! {
+    CodepayParamsBuilder => CodepayParams {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            amount: AmountInfo => validation::amount,
+            scene_info: CodepaySceneInfo,
+            payer: CodepayPayer => check_auth_code,
+        }
+        optional {
+            appid: String,
+            mchid: String,
+            attach: String => validation::attach,
+            goods_tag: String,
+            support_fapiao: bool,
+            settle_info: SettleInfo,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+        }
+    }
+}
+
+/// 付款码为18位纯数字，以10、11、12、13、14、15开头
+fn check_auth_code(field: &str, value: &CodepayPayer, errors: &mut Vec<ValidationError>) {
+    let code = value.auth_code.as_str();
+    if code.len() != 18
+        || !code.bytes().all(|b| b.is_ascii_digit())
+        || !matches!(&code[..2], "10" | "11" | "12" | "13" | "14" | "15")
+    {
+        errors.push(ValidationError::new(
+            field,
+            "auth_code must be 18 digits starting with 10-15",
+        ));
+    }
+}
+
+impl CodepayParams {
+    pub fn new<S: AsRef<str>>(
+        description: S,
+        out_trade_no: S,
+        amount: AmountInfo,
+        auth_code: S,
+        scene_info: CodepaySceneInfo,
+    ) -> Self {
+        Self {
+            appid: None,
+            mchid: None,
+            description: description.as_ref().to_string(),
+            out_trade_no: out_trade_no.as_ref().to_string(),
+            amount,
+            scene_info,
+            payer: CodepayPayer {
+                auth_code: auth_code.as_ref().to_string(),
+            },
+            attach: None,
+            goods_tag: None,
+            support_fapiao: None,
+            settle_info: None,
+        }
+    }
+}
+
+/// 撤销付款码订单
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ReverseParams {
+    ///【公众账号ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 微信支付商户号，不设置时使用WechatPay配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mchid: Option<String>,
+}
+
+params_builder! {
+    ReverseParamsBuilder => ReverseParams {
+        required {}
+        optional {
+            appid: String,
+            mchid: String,
+        }
+        defaults {
+            appid => appid,
+            mchid => mch_id,
+        }
+    }
+}
+
+macro_rules! impl_expire_in {
+    ($($builder:ident),*) => {
+        $(
+            impl $builder {
+                /// 设置订单在`duration`后失效，按北京时间计算
+                pub fn expire_in(self, duration: Duration) -> Self {
+                    self.time_expire(datetime::expire_in(duration))
+                }
+            }
+        )*
+    };
+}
+
+impl_expire_in!(
+    JsapiParamsBuilder,
+    MicroParamsBuilder,
+    NativeParamsBuilder,
+    AppParamsBuilder,
+    H5ParamsBuilder
+);
+
 #[derive(Serialize, Debug, Clone)]
 pub struct TransferSceneReportInfo {
     // 【信息类型】 不能超过15个字符，商户所属转账场景下的信息类型，此字段内容为固定值，需严格按照转账场景报备信息字段说明传参。
@@ -542,8 +979,9 @@ unsafe impl Sync for TransferSceneReportInfo {}
 
 #[derive(Serialize, Debug, Clone)]
 pub struct TransferBillsParams {
-    ///【商户AppID】 微信支付商户号绑定的appid
-    pub appid: String,
+    ///【商户AppID】 微信支付商户号绑定的appid，不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
     ///【商户单号】 商户系统内部的商家单号，要求此参数只能由数字、大小写字母组成，在商户系统内部唯一
     pub out_bill_no: String,
     ///【转账场景ID】 该笔转账使用的转账场景，可前往“商户平台-产品中心-商家转账”中申请。如：1001-现金营销
@@ -555,9 +993,10 @@ pub struct TransferBillsParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_name: Option<String>,
     ///【转账金额】 转账金额单位为“分”。
-    pub transfer_amount: i32,
-    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP
-    pub notify_url: String,
+    pub transfer_amount: Money,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，通知URL必须为外网可访问的URL，不能携带参数。 公网域名必须为HTTPS，如果是走专线接入，使用专线NAT IP或者私有回调域名可使用HTTP。不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
     ///【转账备注】 转账备注，用户收款时可见该备注信息，UTF8编码，最多允许32个字符
     pub transfer_remark: String,
 
@@ -568,9 +1007,26 @@ pub struct TransferBillsParams {
     pub transfer_scene_report_infos: Vec<TransferSceneReportInfo>,
 }
 
-impl ParamsTrait for TransferBillsParams {
-    fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+params_builder! {
+    TransferBillsParamsBuilder => TransferBillsParams {
+        required {
+            out_bill_no: String => validation::out_bill_no,
+            transfer_scene_id: String,
+            openid: String,
+            transfer_amount: Money => validation::money,
+            transfer_remark: String => validation::transfer_remark,
+            transfer_scene_report_infos: Vec<TransferSceneReportInfo>,
+        }
+        optional {
+            appid: String,
+            notify_url: String => validation::notify_url,
+            user_name: String,
+            user_recv_perception: String,
+        }
+        defaults {
+            appid => appid,
+            notify_url => notify_url,
+        }
     }
 }
 
@@ -598,25 +1054,222 @@ pub struct WechatPayNotifySource {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WechatPayNotify {
     pub id: String,
-    pub create_time: String,
+    #[serde(with = "datetime::rfc3339")]
+    pub create_time: DateTime<FixedOffset>,
     pub event_type: String,
     pub resource_type: String,
     pub resource: WechatPayNotifySource,
     pub summary: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromotionGoodsDetail {
+    ///【商品编码】
+    pub goods_id: String,
+    ///【商品数量】
+    pub quantity: i32,
+    ///【商品单价】 单位为分
+    pub unit_price: Money,
+    ///【商品优惠金额】 单位为分
+    pub discount_amount: Money,
+    ///【商品备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_remark: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromotionDetail {
+    ///【券ID】
+    pub coupon_id: String,
+    ///【优惠名称】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    ///【优惠范围】 GLOBAL：全场代金券，SINGLE：单品优惠
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    ///【优惠类型】 CASH：充值型代金券，NOCASH：免充值型代金券
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub promotion_type: Option<String>,
+    ///【优惠券面额】 单位为分
+    pub amount: Money,
+    ///【活动ID】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_id: Option<String>,
+    ///【微信出资】 单位为分
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wechatpay_contribute: Option<Money>,
+    ///【商户出资】 单位为分
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_contribute: Option<Money>,
+    ///【其他出资】 单位为分
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_contribute: Option<Money>,
+    ///【优惠币种】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+    ///【单品列表】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_detail: Option<Vec<PromotionGoodsDetail>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionSceneInfo {
+    ///【商户端设备号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+}
+
+/// 支付成功通知解密后的交易数据
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WechatPayDecodeData {
+    ///【商户号】
     pub mchid: String,
+    ///【公众账号ID】
     pub appid: String,
+    ///【商户订单号】
     pub out_trade_no: String,
+    ///【微信支付订单号】
     pub transaction_id: String,
-    pub trade_type: String,
-    pub trade_state: String,
+    ///【交易类型】
+    pub trade_type: PayType,
+    ///【交易状态】
+    pub trade_state: TradeState,
+    ///【交易状态描述】
     pub trade_state_desc: String,
-    pub bank_type: String,
-    pub attach: String,
-    pub success_time: String,
-    pub payer: PayerInfo,
+    ///【银行类型】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank_type: Option<BankType>,
+    ///【附加数据】 下单时未传入时不返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+    ///【支付完成时间】
+    #[serde(
+        default,
+        with = "datetime::rfc3339_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub success_time: Option<DateTime<FixedOffset>>,
+    ///【支付者】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<PayerInfo>,
+    ///【订单金额】
     pub amount: AmountInfo,
+    ///【场景信息】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_info: Option<TransactionSceneInfo>,
+    ///【优惠功能】 享受优惠时返回该字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotion_detail: Option<Vec<PromotionDetail>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::WechatPayDecodeData;
+    use crate::money::Money;
+    use crate::pay_type::{BankType, PayType, TradeState};
+    use serde_json::Value;
+
+    /// 微信支付文档中的支付成功通知示例
+    const TRANSACTION_SAMPLE: &str = r#"{
+        "transaction_id": "1217752501201407033233368018",
+        "amount": {
+            "payer_total": 100,
+            "total": 100,
+            "currency": "CNY",
+            "payer_currency": "CNY"
+        },
+        "mchid": "1230000109",
+        "trade_state": "SUCCESS",
+        "bank_type": "CMC",
+        "promotion_detail": [
+            {
+                "amount": 100,
+                "wechatpay_contribute": 0,
+                "coupon_id": "109519",
+                "scope": "GLOBAL",
+                "merchant_contribute": 0,
+                "name": "单品惠-6",
+                "other_contribute": 0,
+                "currency": "CNY",
+                "stock_id": "931386",
+                "goods_detail": [
+                    {
+                        "goods_remark": "商品备注信息",
+                        "quantity": 1,
+                        "discount_amount": 1,
+                        "goods_id": "M1006",
+                        "unit_price": 100
+                    }
+                ]
+            }
+        ],
+        "success_time": "2018-06-08T10:34:56+08:00",
+        "payer": {
+            "openid": "oUpF8uMuAJO_M2pxb1Q9zNjWeS6o"
+        },
+        "out_trade_no": "1217752501201407033233368018",
+        "appid": "wxd678efh567hg6787",
+        "trade_state_desc": "支付成功",
+        "trade_type": "MICROPAY",
+        "attach": "自定义数据",
+        "scene_info": {
+            "device_id": "013467007045764"
+        }
+    }"#;
+
+    /// 未传attach、无优惠的H5支付通知
+    const MINIMAL_SAMPLE: &str = r#"{
+        "mchid": "163971811111",
+        "appid": "wx15f4803f25xxxxx",
+        "out_trade_no": "8e289eebd1f44604b0b27e05f11bcf10",
+        "transaction_id": "4200001926202401125681342683",
+        "trade_type": "MWEB",
+        "trade_state": "SUCCESS",
+        "trade_state_desc": "支付成功",
+        "bank_type": "OTHERS",
+        "success_time": "2024-01-12T10:36:13+08:00",
+        "payer": {
+            "openid": "oAZUY6DittOj59wCzPn6vNgpK2eY"
+        },
+        "amount": {
+            "total": 1,
+            "payer_total": 1,
+            "currency": "CNY",
+            "payer_currency": "CNY"
+        }
+    }"#;
+
+    fn round_trip(sample: &str) -> WechatPayDecodeData {
+        let data: WechatPayDecodeData = serde_json::from_str(sample).unwrap();
+        let expected: Value = serde_json::from_str(sample).unwrap();
+        assert_eq!(serde_json::to_value(&data).unwrap(), expected);
+        data
+    }
+
+    #[test]
+    fn test_transaction_sample() {
+        let data = round_trip(TRANSACTION_SAMPLE);
+        assert_eq!(data.trade_type, PayType::Micropay);
+        assert_eq!(data.trade_state, TradeState::Success);
+        assert_eq!(data.bank_type, Some(BankType::Unknown("CMC".to_string())));
+        assert_eq!(data.amount.payer_total, Some(Money::from_cents(100)));
+        let promotion = &data.promotion_detail.as_ref().unwrap()[0];
+        assert_eq!(promotion.amount, Money::from_cents(100));
+        assert_eq!(
+            promotion.goods_detail.as_ref().unwrap()[0].goods_id,
+            "M1006"
+        );
+        assert_eq!(
+            data.scene_info.unwrap().device_id.as_deref(),
+            Some("013467007045764")
+        );
+    }
+
+    #[test]
+    fn test_minimal_sample() {
+        let data = round_trip(MINIMAL_SAMPLE);
+        assert_eq!(data.trade_type, PayType::H5);
+        assert_eq!(data.attach, None);
+        assert!(data.promotion_detail.is_none());
+    }
 }
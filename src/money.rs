@@ -0,0 +1,273 @@
+use crate::error::PayError;
+use crate::model::Currency;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// 金额，以最小货币单位（分）保存，序列化为整数分。
+/// 反序列化时货币默认为人民币，需要其他币种时由所在结构体的`currency`字段指定。
+/// ```rust
+/// use wechat_pay_rust_sdk::model::Currency;
+/// use wechat_pay_rust_sdk::money::Money;
+///
+/// let price: Money = "12.34".parse().unwrap();
+/// assert_eq!(price.cents(), 1234);
+/// let total = price.checked_mul(3).unwrap();
+/// assert_eq!(total.to_yuan_string(), "37.02");
+/// assert!(price.checked_add(Money::new(1, Currency::USD)).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    cents: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(cents: i64, currency: Currency) -> Self {
+        Self { cents, currency }
+    }
+
+    /// 人民币金额，单位为分
+    pub fn from_cents(cents: i64) -> Self {
+        Self::new(cents, Currency::CNY)
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// 从以元为单位的字符串解析人民币金额，例如"12.34"，最多两位小数
+    pub fn from_yuan(yuan: impl AsRef<str>) -> Result<Self, PayError> {
+        Self::from_yuan_with_currency(yuan, Currency::CNY)
+    }
+
+    /// 从以元（主货币单位）为单位的字符串解析指定币种的金额，
+    /// 小数位数不能超过该币种的辅币位数，日元、韩元不允许小数
+    pub fn from_yuan_with_currency(
+        yuan: impl AsRef<str>,
+        currency: Currency,
+    ) -> Result<Self, PayError> {
+        let value = yuan.as_ref().trim();
+        let invalid = || PayError::MoneyError(format!("invalid amount: {}", value));
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        let exponent = currency.minor_unit_exponent() as usize;
+        let (integer, fraction) = match digits.split_once('.') {
+            Some(_) if exponent == 0 => return Err(invalid()),
+            Some((integer, fraction)) => (integer, fraction),
+            None => (digits, ""),
+        };
+        if integer.is_empty()
+            || fraction.len() > exponent
+            || !integer.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let fraction = if exponent == 0 {
+            0
+        } else {
+            format!("{:0<width$}", fraction, width = exponent)
+                .parse::<i64>()
+                .map_err(|_| invalid())?
+        };
+        let cents = integer
+            .parse::<i64>()
+            .ok()
+            .and_then(|integer| integer.checked_mul(10i64.pow(exponent as u32)))
+            .and_then(|cents| cents.checked_add(fraction))
+            .ok_or_else(invalid)?;
+        Ok(Self::new(if negative { -cents } else { cents }, currency))
+    }
+
+    /// 金额，单位为分
+    pub fn cents(&self) -> i64 {
+        self.cents
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.cents > 0
+    }
+
+    /// 以元（主货币单位）为单位的字符串，例如"12.34"，日元、韩元没有小数部分
+    pub fn to_yuan_string(&self) -> String {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let cents = self.cents.unsigned_abs();
+        let exponent = self.currency.minor_unit_exponent();
+        if exponent == 0 {
+            return format!("{}{}", sign, cents);
+        }
+        let unit = 10u64.pow(exponent);
+        format!(
+            "{}{}.{:0width$}",
+            sign,
+            cents / unit,
+            cents % unit,
+            width = exponent as usize
+        )
+    }
+
+    /// 相加，币种不同或溢出时返回`None`
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        if self.currency != rhs.currency {
+            return None;
+        }
+        self.cents
+            .checked_add(rhs.cents)
+            .map(|cents| Self::new(cents, self.currency))
+    }
+
+    /// 相减，币种不同或溢出时返回`None`
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        if self.currency != rhs.currency {
+            return None;
+        }
+        self.cents
+            .checked_sub(rhs.cents)
+            .map(|cents| Self::new(cents, self.currency))
+    }
+
+    /// 乘以数量，溢出时返回`None`
+    pub fn checked_mul(self, quantity: i64) -> Option<Money> {
+        self.cents
+            .checked_mul(quantity)
+            .map(|cents| Self::new(cents, self.currency))
+    }
+}
+
+/// 只有币种相同的金额可以比较大小，币种不同时返回`None`
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+        self.cents.partial_cmp(&other.cents)
+    }
+}
+
+impl Default for Money {
+    fn default() -> Self {
+        Self::zero(Currency::CNY)
+    }
+}
+
+impl From<i32> for Money {
+    fn from(value: i32) -> Self {
+        Self::from_cents(value as i64)
+    }
+}
+
+impl From<i64> for Money {
+    fn from(value: i64) -> Self {
+        Self::from_cents(value)
+    }
+}
+
+impl FromStr for Money {
+    type Err = PayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_yuan(s)
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.to_yuan_string(), self.currency)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.cents)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(Self::from_cents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{AmountInfo, Currency};
+    use crate::money::Money;
+
+    #[test]
+    fn test_from_yuan() {
+        assert_eq!(Money::from_yuan("12.34").unwrap().cents(), 1234);
+        assert_eq!(Money::from_yuan("12.3").unwrap().cents(), 1230);
+        assert_eq!(Money::from_yuan("12").unwrap().cents(), 1200);
+        assert_eq!(Money::from_yuan("0.01").unwrap().cents(), 1);
+        assert_eq!(Money::from_yuan("-1.05").unwrap().cents(), -105);
+        assert!(Money::from_yuan("1.234").is_err());
+        assert!(Money::from_yuan("1,00").is_err());
+        assert!(Money::from_yuan(".5").is_err());
+        assert!(Money::from_yuan("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_zero_exponent_currency() {
+        let yen = Money::from_yuan_with_currency("1500", Currency::JPY).unwrap();
+        assert_eq!(yen.cents(), 1500);
+        assert_eq!(yen.to_yuan_string(), "1500");
+        assert_eq!(yen.to_string(), "1500 JPY");
+        let won = Money::from_yuan_with_currency("-250", Currency::KRW).unwrap();
+        assert_eq!(won.cents(), -250);
+        assert_eq!(
+            Money::from_yuan_with_currency(won.to_yuan_string(), Currency::KRW).unwrap(),
+            won
+        );
+        assert!(Money::from_yuan_with_currency("15.00", Currency::JPY).is_err());
+        assert!(Money::from_yuan_with_currency("15.", Currency::KRW).is_err());
+        let usd = Money::from_yuan_with_currency("15.5", Currency::USD).unwrap();
+        assert_eq!(usd.to_yuan_string(), "15.50");
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = Money::from_cents(i64::MAX);
+        assert!(a.checked_add(Money::from_cents(1)).is_none());
+        assert!(a.checked_mul(2).is_none());
+        let b = Money::from_cents(150);
+        assert_eq!(b.checked_sub(Money::from_cents(200)).unwrap().cents(), -50);
+        assert!(b.checked_add(Money::new(1, Currency::HKD)).is_none());
+        assert_eq!(Money::from_cents(-5).to_yuan_string(), "-0.05");
+        assert_eq!(Money::new(1999, Currency::USD).to_string(), "19.99 USD");
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(Money::from_cents(100) > Money::from_cents(99));
+        assert_eq!(
+            Money::new(100, Currency::USD).partial_cmp(&Money::from_cents(99)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_amount_serde() {
+        let amount = AmountInfo::from(Money::new(100, Currency::HKD));
+        let json = serde_json::to_string(&amount).unwrap();
+        assert_eq!(json, r#"{"total":100,"currency":"HKD"}"#);
+        let amount: AmountInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(amount.total, Money::new(100, Currency::HKD));
+        let amount: AmountInfo = serde_json::from_str(r#"{"total":1}"#).unwrap();
+        assert_eq!(amount.total, Money::from_cents(1));
+        let amount: AmountInfo = serde_json::from_str(r#"{"total":1,"currency":"XYZ"}"#).unwrap();
+        assert_eq!(amount.total.currency(), Currency::Unknown);
+    }
+}
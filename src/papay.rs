@@ -0,0 +1,323 @@
+//! 委托代扣，预签约、查询和解约签约协议，申请扣款，签约和解约通知
+use crate::datetime;
+use crate::error::ValidationError;
+use crate::macros::params_builder;
+use crate::model::AmountInfo;
+use crate::response::ResponseTrait;
+use crate::validation;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// 预签约的发起方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreEntrustSignType {
+    App,
+    Jsapi,
+    H5,
+    MiniProgram,
+}
+
+impl PreEntrustSignType {
+    pub(crate) fn path(&self) -> &str {
+        match self {
+            PreEntrustSignType::App => "app",
+            PreEntrustSignType::Jsapi => "jsapi",
+            PreEntrustSignType::H5 => "h5",
+            PreEntrustSignType::MiniProgram => "mini-program",
+        }
+    }
+
+    /// JSAPI和小程序预签约必须传入用户的openid
+    pub(crate) fn need_openid(&self) -> bool {
+        matches!(
+            self,
+            PreEntrustSignType::Jsapi | PreEntrustSignType::MiniProgram
+        )
+    }
+}
+
+/// 预签约
+#[derive(Serialize, Debug, Clone)]
+pub struct PreEntrustSignParams {
+    ///预签约的发起方式，决定请求地址，不会序列化到请求体
+    #[serde(skip)]
+    pub sign_type: PreEntrustSignType,
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【委托代扣模板ID】 商户在商户平台配置的代扣模板ID
+    pub plan_id: u64,
+    ///【商户签约协议号】 商户侧唯一
+    pub out_contract_code: String,
+    ///【签约用户的名称】 展示在用户的签约协议中，例如会员账号
+    pub contract_display_account: String,
+    ///【签约信息通知地址】 接收签约、解约通知，通知内容与支付通知不同，需要单独设置
+    pub contract_notify_url: String,
+    ///【用户标识】 JSAPI和小程序预签约时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub openid: Option<String>,
+    ///【用户终端IP】 H5预签约时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_client_ip: Option<String>,
+    ///【签约完成后的跳转地址】 H5预签约时使用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_url: Option<String>,
+}
+
+params_builder! {
+    PreEntrustSignParamsBuilder => PreEntrustSignParams {
+        required {
+            sign_type: PreEntrustSignType,
+            plan_id: u64,
+            out_contract_code: String => validation::out_trade_no,
+            contract_display_account: String,
+            contract_notify_url: String => validation::notify_url,
+        }
+        optional {
+            appid: String,
+            openid: String,
+            payer_client_ip: String,
+            return_url: String,
+        }
+        defaults {
+            appid => appid,
+        }
+        check { PreEntrustSignParams::check }
+    }
+}
+
+impl PreEntrustSignParams {
+    /// JSAPI和小程序预签约需要openid，H5预签约需要用户终端IP
+    fn check(&self, errors: &mut Vec<ValidationError>) {
+        if self.sign_type.need_openid() && self.openid.is_none() {
+            errors.push(ValidationError::new("openid", "is required"));
+        }
+        if self.sign_type == PreEntrustSignType::H5 && self.payer_client_ip.is_none() {
+            errors.push(ValidationError::new("payer_client_ip", "is required"));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreEntrustSignResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【预签约ID】 APP、JSAPI和小程序用于拉起签约页面，有效期2小时
+    pub pre_entrustweb_id: Option<String>,
+    ///【签约跳转链接】 H5预签约返回，在浏览器中打开
+    pub redirect_url: Option<String>,
+}
+
+impl ResponseTrait for PreEntrustSignResponse {}
+
+/// 签约协议状态
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractState {
+    /// 已签约
+    Signed,
+    /// 已解约
+    Terminated,
+    #[serde(other)]
+    Unknown,
+}
+
+/// 签约协议，查询协议和签约、解约通知都返回该结构
+#[derive(Debug, Clone, Deserialize)]
+pub struct Contract {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【商户号】
+    pub mchid: Option<String>,
+    ///【应用ID】
+    pub appid: Option<String>,
+    ///【委托代扣协议ID】
+    pub contract_id: Option<String>,
+    ///【委托代扣模板ID】
+    pub plan_id: Option<u64>,
+    ///【商户签约协议号】
+    pub out_contract_code: Option<String>,
+    ///【签约用户的名称】
+    pub contract_display_account: Option<String>,
+    ///【用户标识】
+    pub openid: Option<String>,
+    ///【协议状态】
+    pub contract_state: Option<ContractState>,
+    ///【签约时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub contract_signed_time: Option<DateTime<FixedOffset>>,
+    ///【协议到期时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub contract_expired_time: Option<DateTime<FixedOffset>>,
+    ///【解约时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub contract_terminated_time: Option<DateTime<FixedOffset>>,
+    ///【解约方式】 USER、MCH、PLATFORM、EXPIRED等
+    pub contract_termination_mode: Option<String>,
+    ///【解约备注】
+    pub contract_termination_remark: Option<String>,
+}
+
+impl ResponseTrait for Contract {}
+
+/// 签约（PAPAY.SIGN）和解约（PAPAY.TERMINATE）通知解密后的内容
+pub type ContractNotifyData = Contract;
+
+/// 解除签约协议
+#[derive(Serialize, Debug, Clone)]
+pub struct TerminateContractParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【解约备注】 展示给用户，不超过32个字符
+    pub contract_termination_remark: String,
+}
+
+params_builder! {
+    TerminateContractParamsBuilder => TerminateContractParams {
+        required {
+            contract_termination_remark: String => validation::transfer_remark,
+        }
+        optional {
+            appid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+impl TerminateContractParams {
+    pub fn new<S: AsRef<str>>(remark: S) -> Self {
+        Self {
+            appid: None,
+            contract_termination_remark: remark.as_ref().to_string(),
+        }
+    }
+}
+
+/// 申请扣款，受理成功后扣款结果通过支付通知返回，也可以按商户订单号查询
+#[derive(Serialize, Debug, Clone)]
+pub struct PapayTransactionParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商品描述】
+    pub description: String,
+    ///【附加数据】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+    ///【商户订单号】
+    pub out_trade_no: String,
+    ///【订单优惠标记】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_tag: Option<String>,
+    ///【通知地址】 不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
+    ///【委托代扣协议ID】
+    pub contract_id: String,
+    ///【订单金额】
+    pub amount: AmountInfo,
+}
+
+params_builder! {
+    PapayTransactionParamsBuilder => PapayTransactionParams {
+        required {
+            description: String => validation::description,
+            out_trade_no: String => validation::out_trade_no,
+            contract_id: String,
+            amount: AmountInfo => validation::amount,
+        }
+        optional {
+            appid: String,
+            attach: String => validation::attach,
+            goods_tag: String,
+            notify_url: String => validation::notify_url,
+        }
+        defaults {
+            appid => appid,
+            notify_url => notify_url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::ParamsTrait;
+    use crate::papay::{
+        Contract, ContractState, PapayTransactionParams, PreEntrustSignParams, PreEntrustSignType,
+        TerminateContractParams,
+    };
+    use crate::pay::WechatPay;
+
+    #[test]
+    fn test_pre_entrust_sign_defaults() {
+        let wechat_pay = WechatPay::new(
+            "wxd678efh567hg6787",
+            "1230000109",
+            "",
+            "",
+            "",
+            "https://mydomain.com/pay/notify",
+        );
+        let mut params = PreEntrustSignParams::builder()
+            .sign_type(PreEntrustSignType::App)
+            .plan_id(12535u64)
+            .out_contract_code("100001256")
+            .contract_display_account("会员账号")
+            .contract_notify_url("https://mydomain.com/papay/notify")
+            .build()
+            .unwrap();
+        params.fill_defaults(&wechat_pay);
+        let json: serde_json::Value = serde_json::from_str(&params.to_json()).unwrap();
+        assert_eq!(json["appid"], "wxd678efh567hg6787");
+        assert_eq!(
+            json["contract_notify_url"],
+            "https://mydomain.com/papay/notify"
+        );
+        assert!(json.get("openid").is_none());
+        assert!(json.get("sign_type").is_none());
+
+        let err = PreEntrustSignParams::builder()
+            .sign_type(PreEntrustSignType::MiniProgram)
+            .plan_id(12535u64)
+            .out_contract_code("100001256")
+            .contract_display_account("会员账号")
+            .contract_notify_url("https://mydomain.com/papay/notify")
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("openid: is required"));
+        params.sign_type = PreEntrustSignType::H5;
+        assert!(params.validate().is_err());
+
+        let err = PapayTransactionParams::builder()
+            .description("会员续费")
+            .out_trade_no("1217752501201407033233368018")
+            .amount(0)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("contract_id: is required"));
+
+        let err = TerminateContractParams::builder()
+            .contract_termination_remark("解".repeat(33))
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("contract_termination_remark"));
+    }
+
+    #[test]
+    fn test_contract() {
+        let contract: Contract = serde_json::from_str(
+            r#"{"mchid":"1230000109","appid":"wxd678efh567hg6787","contract_id":"Wx15463511252015071056489715",
+            "plan_id":12535,"out_contract_code":"100001256","openid":"oUpF8uMuAJO_M2pxb1Q9zNjWeS6o",
+            "contract_state":"TERMINATED","contract_signed_time":"2015-05-20T13:29:35+08:00",
+            "contract_terminated_time":"2015-06-20T13:29:35+08:00","contract_termination_mode":"USER"}"#,
+        )
+        .unwrap();
+        assert_eq!(contract.contract_state, Some(ContractState::Terminated));
+        assert!(
+            contract.contract_terminated_time.unwrap() > contract.contract_signed_time.unwrap()
+        );
+    }
+}
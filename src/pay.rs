@@ -2,7 +2,8 @@ use crate::error::PayError;
 use crate::model::WechatPayDecodeData;
 use crate::request::HttpMethod;
 use crate::response::SignData;
-use crate::{debug, sign, util};
+use crate::secret::SecretString;
+use crate::{debug, secret, sign, util};
 use aes_gcm::aead::{AeadMut, Payload};
 use aes_gcm::{
     aead::{KeyInit},
@@ -12,17 +13,41 @@ use reqwest::header::{HeaderMap, ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT
 use rsa::pkcs8::{DecodePublicKey};
 use rsa::sha2::{Digest, Sha256};
 use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use std::fmt::{Debug, Formatter};
+use std::sync::OnceLock;
 use uuid::Uuid;
+use zeroize::Zeroizing;
 
-#[derive(Debug)]
 pub struct WechatPay {
     pub appid: String,
     pub mch_id: String,
-    pub private_key: String,
+    pub private_key: SecretString,
     pub serial_no: String,
-    pub v3_key: String,
+    pub v3_key: SecretString,
     pub notify_url: String,
     pub base_url: String,
+    client: OnceLock<HttpClient>,
+}
+
+/// 发送请求使用的HTTP客户端，开启`blocking`特性时为同步客户端
+#[cfg(not(feature = "blocking"))]
+pub type HttpClient = reqwest::Client;
+/// 发送请求使用的HTTP客户端，开启`blocking`特性时为同步客户端
+#[cfg(feature = "blocking")]
+pub type HttpClient = reqwest::blocking::Client;
+
+impl Debug for WechatPay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WechatPay")
+            .field("appid", &self.appid)
+            .field("mch_id", &self.mch_id)
+            .field("private_key", &self.private_key)
+            .field("serial_no", &secret::mask(&self.serial_no))
+            .field("v3_key", &self.v3_key)
+            .field("notify_url", &self.notify_url)
+            .field("base_url", &self.base_url)
+            .finish()
+    }
 }
 
 unsafe impl Send for WechatPay {}
@@ -69,6 +94,20 @@ pub trait PayNotifyTrait: WechatPayTrait {
         let data: WechatPayDecodeData = serde_json::from_slice(&plaintext)?;
         Ok(data)
     }
+    /// 解密通知中的`resource`，用于投诉、营销等各类通知
+    fn decrypt_resource<T, S>(
+        &self,
+        ciphertext: S,
+        nonce: S,
+        associated_data: S,
+    ) -> Result<T, PayError>
+        where
+            T: serde::de::DeserializeOwned,
+            S: AsRef<str>,
+    {
+        let plaintext = self.decrypt_bytes(ciphertext, nonce, associated_data)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
     fn decrypt_bytes<S>(
         &self,
         ciphertext: S,
@@ -85,11 +124,11 @@ pub trait PayNotifyTrait: WechatPayTrait {
         }
         let v3_key = self.v3_key();
         let ciphertext = util::base64_decode(ciphertext.as_ref())?;
-        let aes_key = v3_key.as_str().as_bytes();
+        let aes_key = v3_key.as_bytes();
         let mut cipher = Aes256Gcm::new(aes_key.into());
         let payload = Payload {
-            msg: &ciphertext.as_slice(),
-            aad: &associated_data.as_ref().as_bytes(),
+            msg: ciphertext.as_slice(),
+            aad: associated_data.as_ref().as_bytes(),
         };
         let plaintext = cipher
             .decrypt(nonce.as_ref().as_bytes().into(), payload)
@@ -101,12 +140,19 @@ pub trait PayNotifyTrait: WechatPayTrait {
 pub trait WechatPayTrait {
     fn appid(&self) -> String;
     fn mch_id(&self) -> String;
-    fn private_key(&self) -> String;
+    /// 商户私钥，返回值离开作用域时自动清零
+    fn private_key(&self) -> Zeroizing<String>;
     fn serial_no(&self) -> String;
-    fn v3_key(&self) -> String;
+    /// APIv3密钥，返回值离开作用域时自动清零
+    fn v3_key(&self) -> Zeroizing<String>;
     fn notify_url(&self) -> String;
     fn base_url(&self) -> String;
     fn rsa_sign(&self, content: impl AsRef<str>) -> String;
+    /// 使用商户私钥解密接口返回的敏感信息，例如投诉单中的手机号
+    fn decrypt_sensitive(&self, ciphertext: impl AsRef<str>) -> Result<String, PayError> {
+        let private_key = self.private_key();
+        sign::rsa_oaep_decrypt(&private_key, ciphertext.as_ref())
+    }
     fn now_timestamp(&self) -> String {
         chrono::Local::now().timestamp().to_string()
     }
@@ -147,14 +193,14 @@ impl WechatPayTrait for WechatPay {
     fn mch_id(&self) -> String {
         self.mch_id.clone()
     }
-    fn private_key(&self) -> String {
-        self.private_key.clone()
+    fn private_key(&self) -> Zeroizing<String> {
+        Zeroizing::new(self.private_key.expose().to_string())
     }
     fn serial_no(&self) -> String {
         self.serial_no.clone()
     }
-    fn v3_key(&self) -> String {
-        self.v3_key.clone()
+    fn v3_key(&self) -> Zeroizing<String> {
+        Zeroizing::new(self.v3_key.expose().to_string())
     }
     fn notify_url(&self) -> String {
         self.notify_url.clone()
@@ -165,13 +211,12 @@ impl WechatPayTrait for WechatPay {
     }
 
     fn rsa_sign(&self, content: impl AsRef<str>) -> String {
-        let private_key = self.private_key.as_ref();
-        sign::sha256_sign(private_key, content.as_ref())
+        sign::sha256_sign(self.private_key.expose(), content.as_ref())
     }
 }
 
 impl WechatPay {
-    fn with_base_url(mut self, base_url: impl AsRef<str>) -> Self {
+    pub fn with_base_url(mut self, base_url: impl AsRef<str>) -> Self {
         self.base_url = base_url.as_ref().to_string();
         self
     }
@@ -186,14 +231,30 @@ impl WechatPay {
         Self {
             appid: appid.as_ref().to_string(),
             mch_id: mch_id.as_ref().to_string(),
-            private_key: private_key.as_ref().to_string(),
+            private_key: SecretString::new(private_key.as_ref()),
             serial_no: serial_no.as_ref().to_string(),
-            v3_key: v3_key.as_ref().to_string(),
+            v3_key: SecretString::new(v3_key.as_ref()),
             notify_url: notify_url.as_ref().to_string(),
             base_url: "https://api.mch.weixin.qq.com".to_string(),
+            client: OnceLock::new(),
         }
     }
 
+    /// 使用指定的HTTP客户端，多个商户可以共用同一个客户端的连接池
+    pub fn with_client(mut self, client: HttpClient) -> Self {
+        self.client = OnceLock::from(client);
+        self
+    }
+
+    /// 是否已经通过`with_client`或`with_identity`设置了HTTP客户端
+    pub(crate) fn has_client(&self) -> bool {
+        self.client.get().is_some()
+    }
+
+    pub(crate) fn client(&self) -> &HttpClient {
+        self.client.get_or_init(HttpClient::new)
+    }
+
     #[cfg(feature = "debug-print")]
     pub fn open_debug(&self) {
         std::env::set_var("RUST_LOG", "oss=debug");
@@ -203,16 +264,22 @@ impl WechatPay {
             .init();
     }
 
+    /// 从环境变量加载配置，配置缺失或不合法时panic，需要处理错误时使用`try_from_env`
     pub fn from_env() -> Self {
-        let appid = std::env::var("WECHAT_APPID").expect("WECHAT_APPID not found");
-        let mch_id = std::env::var("WECHAT_MCH_ID").expect("WECHAT_MCH_ID not found");
-        let private_key =
-            std::env::var("WECHAT_PRIVATE_KEY").expect("WECHAT_PRIVATE_KEY not found");
-        let serial_no = std::env::var("WECHAT_SERIAL_NO").expect("WECHAT_SERIAL_NO not found");
-        let v3_key = std::env::var("WECHAT_V3_KEY").expect("WECHAT_V3_KEY not found");
-        let notify_url = std::env::var("WECHAT_NOTIFY_URL").expect("WECHAT_NOTIFY_URL not found");
-        let private_key = std::fs::read_to_string(private_key).expect("read private key error");
-        Self::new(appid, mch_id, private_key, serial_no, v3_key, notify_url)
+        Self::try_from_env().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn authorization(
+        &self,
+        nonce_str: &str,
+        signature: &str,
+        timestamp: i64,
+        serial_no: &str,
+    ) -> String {
+        format!(
+            "WECHATPAY2-SHA256-RSA2048 mchid=\"{}\",nonce_str=\"{}\",signature=\"{}\",timestamp=\"{}\",serial_no=\"{}\"",
+            self.mch_id, nonce_str, signature, timestamp, serial_no,
+        )
     }
 
     pub(crate) fn build_header(
@@ -231,17 +298,18 @@ impl WechatPay {
             "{}\n{}\n{}\n{}\n{}\n",
             method, url, timestamp, nonce_str, body,
         );
-        debug!("rsa_sign message: {}", message);
+        debug!("rsa_sign message: {}", secret::mask_sign_message(&message));
         let signature = self.rsa_sign(message);
-        let authorization = format!(
-            "WECHATPAY2-SHA256-RSA2048 mchid=\"{}\",nonce_str=\"{}\",signature=\"{}\",timestamp=\"{}\",serial_no=\"{}\"",
-            self.mch_id,
-            nonce_str,
-            signature,
-            timestamp,
-            serial_no,
+        let authorization = self.authorization(&nonce_str, &signature, timestamp, &serial_no);
+        debug!(
+            "authorization: {}",
+            self.authorization(
+                &nonce_str,
+                &secret::mask(&signature),
+                timestamp,
+                &secret::mask(&serial_no)
+            )
         );
-        debug!("authorization: {}", authorization);
         let mut headers = HeaderMap::new();
         headers.insert(ACCEPT, "application/json".parse().unwrap());
         let chrome_agent = "Mozilla/5.0 (Linux; Android 10; Redmi K30 Pro) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/86.0.4240.198 Mobile Safari/537.36";
@@ -252,6 +320,39 @@ impl WechatPay {
     }
 }
 
+/// 去掉下载地址中的协议和域名，签名使用绝对路径和查询参数
+pub(crate) fn url_path(url: &str) -> &str {
+    url.split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|index| &rest[index..]))
+        .unwrap_or(url)
+}
+
+/// 根据微信支付返回的错误内容生成错误，例如`{"code":"ORDER_CLOSED","message":"订单已关闭"}`
+/// 打开h5_url使用的客户端，由调用方处理跳转，避免自动跳转时覆盖referer。
+/// 所有商户共用，使用默认设置，不包含`with_client`或`with_identity`设置的代理、超时和证书
+pub(crate) fn no_redirect_client() -> Result<&'static HttpClient, PayError> {
+    static CLIENT: OnceLock<HttpClient> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = HttpClient::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+pub(crate) fn wechat_error(status: u16, body: &str) -> PayError {
+    #[derive(serde::Deserialize)]
+    struct ErrorBody {
+        code: String,
+        message: String,
+    }
+    match serde_json::from_str::<ErrorBody>(body) {
+        Ok(error) => PayError::WechatError(format!("{}: {}", error.code, error.message)),
+        Err(_) => PayError::WechatError(format!("http status {}: {}", status, body)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::pay::{PayNotifyTrait, WechatPay, WechatPayTrait};
@@ -1,24 +1,241 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, PartialEq, Eq)]
+/// 交易类型，序列化为微信支付返回的`trade_type`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PayType {
+    ///小程序支付，交易类型与JSAPI相同
     Micro,
     Jsapi,
     Native,
     App,
+    ///H5支付，交易类型为MWEB
     H5,
     Qrcode,
+    ///付款码支付
+    Micropay,
+    ///刷脸支付
+    Facepay,
+    ///未知的交易类型
+    Unknown(String),
+}
+
+impl PayType {
+    /// 微信支付接口中的`trade_type`取值
+    pub fn as_str(&self) -> &str {
+        match self {
+            PayType::Micro | PayType::Jsapi => "JSAPI",
+            PayType::Native => "NATIVE",
+            PayType::App => "APP",
+            PayType::H5 => "MWEB",
+            PayType::Qrcode => "QRCODE",
+            PayType::Micropay => "MICROPAY",
+            PayType::Facepay => "FACEPAY",
+            PayType::Unknown(value) => value.as_str(),
+        }
+    }
 }
 
 impl Display for PayType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<&str> for PayType {
+    fn from(value: &str) -> Self {
+        match value {
+            "JSAPI" => PayType::Jsapi,
+            "NATIVE" => PayType::Native,
+            "APP" => PayType::App,
+            "MWEB" | "H5" => PayType::H5,
+            "QRCODE" => PayType::Qrcode,
+            "MICROPAY" => PayType::Micropay,
+            "FACEPAY" => PayType::Facepay,
+            other => PayType::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for PayType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PayType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|value| PayType::from(value.as_str()))
+    }
+}
+
+/// 交易状态
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TradeState {
+    ///支付成功
+    Success,
+    ///转入退款
+    Refund,
+    ///未支付
+    NotPay,
+    ///已关闭
+    Closed,
+    ///已撤销（仅付款码支付会返回）
+    Revoked,
+    ///用户支付中（仅付款码支付会返回）
+    UserPaying,
+    ///支付失败（仅付款码支付会返回）
+    PayError,
+    ///未知的交易状态
+    Unknown(String),
+}
+
+impl TradeState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TradeState::Success => "SUCCESS",
+            TradeState::Refund => "REFUND",
+            TradeState::NotPay => "NOTPAY",
+            TradeState::Closed => "CLOSED",
+            TradeState::Revoked => "REVOKED",
+            TradeState::UserPaying => "USERPAYING",
+            TradeState::PayError => "PAYERROR",
+            TradeState::Unknown(value) => value.as_str(),
+        }
+    }
+
+    /// 是否为终态，支付成功、转入退款、已关闭、已撤销和支付失败的订单状态不会再变化。
+    /// 未知的状态（例如ACCEPT已接收，等待扣款）按未完成处理
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TradeState::Success
+                | TradeState::Refund
+                | TradeState::Closed
+                | TradeState::Revoked
+                | TradeState::PayError
+        )
+    }
+}
+
+impl Display for TradeState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<&str> for TradeState {
+    fn from(value: &str) -> Self {
+        match value {
+            "SUCCESS" => TradeState::Success,
+            "REFUND" => TradeState::Refund,
+            "NOTPAY" => TradeState::NotPay,
+            "CLOSED" => TradeState::Closed,
+            "REVOKED" => TradeState::Revoked,
+            "USERPAYING" => TradeState::UserPaying,
+            "PAYERROR" => TradeState::PayError,
+            other => TradeState::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for TradeState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TradeState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|value| TradeState::from(value.as_str()))
+    }
+}
+
+/// 付款银行类型，例如`ICBC_DEBIT`表示工商银行借记卡
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BankType {
+    ///其他（银行卡以外），例如零钱
+    Others,
+    ///借记卡，内容为银行编码
+    Debit(String),
+    ///信用卡，内容为银行编码
+    Credit(String),
+    ///其他未识别的类型
+    Unknown(String),
+}
+
+impl Display for BankType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            PayType::Micro => write!(f, "MICRO"),
-            PayType::Jsapi => write!(f, "JSAPI"),
-            PayType::Native => write!(f, "NATIVE"),
-            PayType::App => write!(f, "APP"),
-            PayType::H5 => write!(f, "H5"),
-            PayType::Qrcode => write!(f, "QRCODE"),
+            BankType::Others => write!(f, "OTHERS"),
+            BankType::Debit(bank) => write!(f, "{}_DEBIT", bank),
+            BankType::Credit(bank) => write!(f, "{}_CREDIT", bank),
+            BankType::Unknown(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<&str> for BankType {
+    fn from(value: &str) -> Self {
+        if value == "OTHERS" {
+            return BankType::Others;
         }
+        match (value.strip_suffix("_DEBIT"), value.strip_suffix("_CREDIT")) {
+            (Some(bank), _) if !bank.is_empty() => BankType::Debit(bank.to_string()),
+            (_, Some(bank)) if !bank.is_empty() => BankType::Credit(bank.to_string()),
+            _ => BankType::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl Serialize for BankType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BankType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|value| BankType::from(value.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pay_type::{BankType, PayType, TradeState};
+
+    #[test]
+    fn test_pay_type_serde() {
+        let pay_type: PayType = serde_json::from_str(r#""MWEB""#).unwrap();
+        assert_eq!(pay_type, PayType::H5);
+        assert_eq!(serde_json::to_string(&pay_type).unwrap(), r#""MWEB""#);
+        let pay_type: PayType = serde_json::from_str(r#""NEWTYPE""#).unwrap();
+        assert_eq!(pay_type, PayType::Unknown("NEWTYPE".to_string()));
+        assert_eq!(
+            serde_json::to_string(&PayType::Micro).unwrap(),
+            r#""JSAPI""#
+        );
+        assert_eq!(PayType::H5.to_string(), "MWEB");
+    }
+
+    #[test]
+    fn test_trade_state_serde() {
+        let state: TradeState = serde_json::from_str(r#""USERPAYING""#).unwrap();
+        assert_eq!(state, TradeState::UserPaying);
+        assert!(!state.is_terminal());
+        let state: TradeState = serde_json::from_str(r#""ACCEPT""#).unwrap();
+        assert_eq!(state, TradeState::Unknown("ACCEPT".to_string()));
+        assert!(!state.is_terminal());
+        assert!(TradeState::Revoked.is_terminal());
+        assert_eq!(serde_json::to_string(&state).unwrap(), r#""ACCEPT""#);
+    }
+
+    #[test]
+    fn test_bank_type() {
+        assert_eq!(BankType::from("ICBC_DEBIT"), BankType::Debit("ICBC".into()));
+        assert_eq!(BankType::from("CMB_CREDIT"), BankType::Credit("CMB".into()));
+        assert_eq!(BankType::from("OTHERS"), BankType::Others);
+        assert_eq!(BankType::from("LQT"), BankType::Unknown("LQT".into()));
+        assert_eq!(BankType::Credit("CMB".into()).to_string(), "CMB_CREDIT");
     }
-}
\ No newline at end of file
+}
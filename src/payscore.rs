@@ -0,0 +1,765 @@
+//! 微信支付分，服务订单的创建、查询、取消、修改、完结、同步、收款，用户授权和调起支付分的extraData
+use crate::error::ValidationError;
+use crate::macros::params_builder;
+use crate::money::Money;
+use crate::pay::WechatPayTrait;
+use crate::response::ResponseTrait;
+use crate::v2::SignType;
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 后付费项目
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostPayment {
+    ///【付费名称】
+    pub name: String,
+    ///【付费金额】 单位为分，创建订单时可不填，完结订单时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Money>,
+    ///【付费说明】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    ///【付费数量】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+/// 后付费商户优惠
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostDiscount {
+    ///【优惠名称】
+    pub name: String,
+    ///【优惠说明】
+    pub description: String,
+    ///【优惠金额】 单位为分，创建订单时可不填，完结订单时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Money>,
+    ///【优惠数量】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+/// 服务时间段
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimeRange {
+    ///【服务开始时间】 格式为yyyyMMddHHmmss或yyyyMMdd，OnAccept表示用户确认订单成功时间
+    pub start_time: String,
+    ///【服务开始时间备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time_remark: Option<String>,
+    ///【预计服务结束时间】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+    ///【预计服务结束时间备注】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time_remark: Option<String>,
+}
+
+/// 服务位置
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServiceLocation {
+    ///【服务开始地点】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_location: Option<String>,
+    ///【预计服务结束位置】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_location: Option<String>,
+}
+
+/// 订单风险金
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RiskFund {
+    ///【风险金名称】 DEPOSIT、ADVANCE、CASH_DEPOSIT、ESTIMATE_ORDER_COST
+    pub name: String,
+    ///【风险金额】 单位为分
+    pub amount: Money,
+    ///【风险说明】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// 创建支付分订单
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateServiceOrderParams {
+    ///【商户服务订单号】
+    pub out_order_no: String,
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【服务ID】
+    pub service_id: String,
+    ///【服务信息】 用于介绍本订单所提供的服务
+    pub service_introduction: String,
+    ///【后付费项目】
+    pub post_payments: Vec<PostPayment>,
+    ///【后付费商户优惠】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_discounts: Option<Vec<PostDiscount>>,
+    ///【服务时间段】
+    pub time_range: TimeRange,
+    ///【服务位置】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<ServiceLocation>,
+    ///【订单风险金】
+    pub risk_fund: RiskFund,
+    ///【商户数据包】 不超过256个字符，原样返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+    ///【商户回调地址】 不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
+    ///【用户标识】 需要用户确认时可不填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub openid: Option<String>,
+    ///【是否需要用户确认】
+    pub need_user_confirm: bool,
+}
+
+params_builder! {
+    CreateServiceOrderParamsBuilder => CreateServiceOrderParams {
+        required {
+            out_order_no: String => validation::out_trade_no,
+            service_id: String,
+            service_introduction: String,
+            post_payments: Vec<PostPayment>,
+            time_range: TimeRange,
+            risk_fund: RiskFund,
+            need_user_confirm: bool,
+        }
+        optional {
+            appid: String,
+            post_discounts: Vec<PostDiscount>,
+            location: ServiceLocation,
+            attach: String,
+            notify_url: String => validation::notify_url,
+            openid: String,
+        }
+        defaults {
+            appid => appid,
+            notify_url => notify_url,
+        }
+    }
+}
+
+/// 支付分订单，创建、查询、取消、修改、完结订单和PAYSCORE.USER_CONFIRM、PAYSCORE.USER_PAID通知都返回该结构
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceOrder {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【应用ID】
+    pub appid: Option<String>,
+    ///【商户号】
+    pub mchid: Option<String>,
+    ///【商户服务订单号】
+    pub out_order_no: Option<String>,
+    ///【服务ID】
+    pub service_id: Option<String>,
+    ///【服务信息】
+    pub service_introduction: Option<String>,
+    ///【服务订单状态】 CREATED、DOING、DONE、REVOKED、EXPIRED
+    pub state: Option<String>,
+    ///【订单状态说明】 USER_CONFIRM、MCH_COMPLETE等
+    pub state_description: Option<String>,
+    ///【商户收款总金额】
+    pub total_amount: Option<Money>,
+    ///【后付费项目】
+    #[serde(default)]
+    pub post_payments: Vec<PostPayment>,
+    ///【后付费商户优惠】
+    #[serde(default)]
+    pub post_discounts: Vec<PostDiscount>,
+    ///【订单风险金】
+    pub risk_fund: Option<RiskFund>,
+    ///【服务时间段】
+    pub time_range: Option<TimeRange>,
+    ///【服务位置】
+    pub location: Option<ServiceLocation>,
+    ///【商户数据包】
+    pub attach: Option<String>,
+    ///【商户回调地址】
+    pub notify_url: Option<String>,
+    ///【微信支付服务订单号】
+    pub order_id: Option<String>,
+    ///【跳转微信侧小程序订单数据】 用于生成extraData
+    pub package: Option<String>,
+    ///【是否需要收款】
+    pub need_collection: Option<bool>,
+    ///【收款信息】
+    pub collection: Option<Collection>,
+    ///【用户标识】
+    pub openid: Option<String>,
+}
+
+impl ResponseTrait for ServiceOrder {}
+
+/// 收款明细
+#[derive(Debug, Clone, Deserialize)]
+pub struct Collection {
+    ///【收款状态】 USER_PAYING、USER_PAID
+    pub state: String,
+    ///【总收款金额】
+    pub total_amount: Option<Money>,
+    ///【待收金额】
+    pub paying_amount: Option<Money>,
+    ///【已收金额】
+    pub paid_amount: Option<Money>,
+    ///【收款明细列表】
+    #[serde(default)]
+    pub details: Vec<CollectionDetail>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionDetail {
+    ///【收款序号】
+    pub seq: Option<u32>,
+    ///【单笔收款金额】
+    pub amount: Option<Money>,
+    ///【收款成功渠道】 NEWTON、MCH
+    pub paid_type: Option<String>,
+    ///【收款成功时间】 格式为yyyyMMddHHmmss
+    pub paid_time: Option<String>,
+    ///【微信支付交易单号】
+    pub transaction_id: Option<String>,
+}
+
+/// 收款等只需要服务ID的操作
+#[derive(Serialize, Debug, Clone)]
+pub struct ServiceOrderActionParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【服务ID】
+    pub service_id: String,
+}
+
+params_builder! {
+    ServiceOrderActionParamsBuilder => ServiceOrderActionParams {
+        required {
+            service_id: String,
+        }
+        optional {
+            appid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+impl ServiceOrderActionParams {
+    pub fn new<S: AsRef<str>>(service_id: S) -> Self {
+        Self {
+            appid: None,
+            service_id: service_id.as_ref().to_string(),
+        }
+    }
+}
+
+/// 取消订单
+#[derive(Serialize, Debug, Clone)]
+pub struct CancelServiceOrderParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【服务ID】
+    pub service_id: String,
+    ///【取消原因】 不超过50个字符
+    pub reason: String,
+}
+
+params_builder! {
+    CancelServiceOrderParamsBuilder => CancelServiceOrderParams {
+        required {
+            service_id: String,
+            reason: String => validation::service_order_reason,
+        }
+        optional {
+            appid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+impl CancelServiceOrderParams {
+    pub fn new<S: AsRef<str>>(service_id: S, reason: S) -> Self {
+        Self {
+            appid: None,
+            service_id: service_id.as_ref().to_string(),
+            reason: reason.as_ref().to_string(),
+        }
+    }
+}
+
+/// 修改订单金额
+#[derive(Serialize, Debug, Clone)]
+pub struct ModifyServiceOrderParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【服务ID】
+    pub service_id: String,
+    ///【后付费项目】
+    pub post_payments: Vec<PostPayment>,
+    ///【后付费商户优惠】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_discounts: Option<Vec<PostDiscount>>,
+    ///【总金额】 等于后付费项目金额之和减去商户优惠金额之和
+    pub total_amount: Money,
+    ///【修改原因】 不超过50个字符
+    pub reason: String,
+}
+
+params_builder! {
+    ModifyServiceOrderParamsBuilder => ModifyServiceOrderParams {
+        required {
+            service_id: String,
+            post_payments: Vec<PostPayment>,
+            total_amount: Money,
+            reason: String => validation::service_order_reason,
+        }
+        optional {
+            appid: String,
+            post_discounts: Vec<PostDiscount>,
+        }
+        defaults {
+            appid => appid,
+        }
+        check { ModifyServiceOrderParams::check }
+    }
+}
+
+impl ModifyServiceOrderParams {
+    fn check(&self, errors: &mut Vec<ValidationError>) {
+        check_total_amount(
+            &self.post_payments,
+            &self.post_discounts,
+            &self.total_amount,
+            errors,
+        );
+    }
+}
+
+/// 完结订单
+#[derive(Serialize, Debug, Clone)]
+pub struct ServiceOrderAmountParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【服务ID】
+    pub service_id: String,
+    ///【后付费项目】
+    pub post_payments: Vec<PostPayment>,
+    ///【后付费商户优惠】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_discounts: Option<Vec<PostDiscount>>,
+    ///【总金额】 等于后付费项目金额之和减去商户优惠金额之和
+    pub total_amount: Money,
+    ///【服务时间段】 可填写实际服务时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_range: Option<TimeRange>,
+    ///【服务位置】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<ServiceLocation>,
+    ///【微信支付服务分账标记】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profit_sharing: Option<bool>,
+    ///【订单优惠标记】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_tag: Option<String>,
+}
+
+params_builder! {
+    ServiceOrderAmountParamsBuilder => ServiceOrderAmountParams {
+        required {
+            service_id: String,
+            post_payments: Vec<PostPayment>,
+            total_amount: Money,
+        }
+        optional {
+            appid: String,
+            post_discounts: Vec<PostDiscount>,
+            time_range: TimeRange,
+            location: ServiceLocation,
+            profit_sharing: bool,
+            goods_tag: String,
+        }
+        defaults {
+            appid => appid,
+        }
+        check { ServiceOrderAmountParams::check }
+    }
+}
+
+impl ServiceOrderAmountParams {
+    fn check(&self, errors: &mut Vec<ValidationError>) {
+        check_total_amount(
+            &self.post_payments,
+            &self.post_discounts,
+            &self.total_amount,
+            errors,
+        );
+    }
+}
+
+/// 校验总金额等于后付费项目之和减去商户优惠之和
+fn check_total_amount(
+    post_payments: &[PostPayment],
+    post_discounts: &Option<Vec<PostDiscount>>,
+    total_amount: &Money,
+    errors: &mut Vec<ValidationError>,
+) {
+    let payments = post_payments
+        .iter()
+        .map(|payment| {
+            payment
+                .amount
+                .map(|amount| amount.cents())
+                .unwrap_or_default()
+        })
+        .sum::<i64>();
+    let discounts = post_discounts
+        .iter()
+        .flatten()
+        .map(|discount| {
+            discount
+                .amount
+                .map(|amount| amount.cents())
+                .unwrap_or_default()
+        })
+        .sum::<i64>();
+    if payments - discounts != total_amount.cents() {
+        errors.push(ValidationError::new(
+            "total_amount",
+            "must equal post_payments minus post_discounts",
+        ));
+    }
+}
+
+/// 同步服务订单信息
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncServiceOrderParams {
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【服务ID】
+    pub service_id: String,
+    ///【场景类型】 Order_Paid表示用户已通过其他方式完成付款
+    #[serde(rename = "type")]
+    pub sync_type: String,
+    ///【内容信息详情】
+    pub detail: SyncDetail,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncDetail {
+    ///【收款成功时间】 格式为yyyyMMddHHmmss
+    pub paid_time: String,
+}
+
+params_builder! {
+    SyncServiceOrderParamsBuilder => SyncServiceOrderParams {
+        required {
+            service_id: String,
+            sync_type: String,
+            detail: SyncDetail,
+        }
+        optional {
+            appid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+impl SyncServiceOrderParams {
+    /// 用户已通过其他方式付款
+    pub fn order_paid<S: AsRef<str>>(service_id: S, paid_time: S) -> Self {
+        Self {
+            appid: None,
+            service_id: service_id.as_ref().to_string(),
+            sync_type: "Order_Paid".to_string(),
+            detail: SyncDetail {
+                paid_time: paid_time.as_ref().to_string(),
+            },
+        }
+    }
+}
+
+/// 商户预授权
+#[derive(Serialize, Debug, Clone)]
+pub struct PayScorePermissionParams {
+    ///【服务ID】
+    pub service_id: String,
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【授权协议号】 商户侧授权协议号
+    pub authorization_code: String,
+    ///【商户回调地址】 不设置时使用WechatPay配置的通知地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<String>,
+}
+
+params_builder! {
+    PayScorePermissionParamsBuilder => PayScorePermissionParams {
+        required {
+            service_id: String,
+            authorization_code: String,
+        }
+        optional {
+            appid: String,
+            notify_url: String => validation::notify_url,
+        }
+        defaults {
+            appid => appid,
+            notify_url => notify_url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayScorePermissionResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【预授权token】 用于跳转支付分授权页面
+    pub apply_permissions_token: Option<String>,
+}
+
+impl ResponseTrait for PayScorePermissionResponse {}
+
+/// 解除用户授权关系
+#[derive(Serialize, Debug, Clone)]
+pub struct TerminatePermissionParams {
+    ///【服务ID】
+    pub service_id: String,
+    ///【应用ID】 不设置时使用WechatPay配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【撤销原因】
+    pub reason: String,
+}
+
+params_builder! {
+    TerminatePermissionParamsBuilder => TerminatePermissionParams {
+        required {
+            service_id: String,
+            reason: String,
+        }
+        optional {
+            appid: String,
+        }
+        defaults {
+            appid => appid,
+        }
+    }
+}
+
+/// 用户授权状态，也是PAYSCORE.USER_OPEN_SERVICE、PAYSCORE.USER_CLOSE_SERVICE通知的内容
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserServiceState {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【应用ID】
+    pub appid: Option<String>,
+    ///【商户号】
+    pub mchid: Option<String>,
+    ///【服务ID】
+    pub service_id: Option<String>,
+    ///【用户标识】
+    pub openid: Option<String>,
+    ///【服务授权状态】 查询时返回AVAILABLE、UNAVAILABLE
+    pub use_service_state: Option<String>,
+    ///【授权状态】 通知中返回USER_OPEN_SERVICE、USER_CLOSE_SERVICE
+    pub user_service_status: Option<String>,
+    ///【授权协议号】
+    pub authorization_code: Option<String>,
+    ///【授权/解除授权时间】 格式为yyyyMMddHHmmss
+    pub openorclose_time: Option<String>,
+}
+
+impl ResponseTrait for UserServiceState {}
+
+/// PAYSCORE.USER_CONFIRM（用户确认订单）通知解密后的内容
+pub type UserConfirmNotifyData = ServiceOrder;
+
+/// PAYSCORE.USER_PAID（用户支付成功）通知解密后的内容
+pub type UserPaidNotifyData = ServiceOrder;
+
+/// PAYSCORE.USER_OPEN_SERVICE（用户授权）通知解密后的内容
+pub type UserOpenServiceNotifyData = UserServiceState;
+
+/// PAYSCORE.USER_CLOSE_SERVICE（用户解除授权）通知解密后的内容
+pub type UserCloseServiceNotifyData = UserServiceState;
+
+/// 调起支付分订单详情页的extraData，使用APIv3密钥进行HMAC-SHA256签名
+#[derive(Serialize, Debug, Clone)]
+pub struct PayScoreExtraData {
+    pub mch_id: String,
+    pub package: String,
+    pub timestamp: String,
+    pub nonce_str: String,
+    pub sign_type: String,
+    pub sign: String,
+}
+
+impl PayScoreExtraData {
+    /// `package`为创建订单或查询订单返回的package
+    pub fn new(pay: &impl WechatPayTrait, package: impl AsRef<str>) -> Self {
+        let mut map = BTreeMap::new();
+        map.insert("mch_id".to_string(), pay.mch_id());
+        map.insert("package".to_string(), package.as_ref().to_string());
+        map.insert("timestamp".to_string(), pay.now_timestamp());
+        map.insert("nonce_str".to_string(), crate::util::random_trade_no());
+        map.insert(
+            "sign_type".to_string(),
+            SignType::HmacSha256.as_str().to_string(),
+        );
+        Self::sign(pay, map)
+    }
+
+    fn sign(pay: &impl WechatPayTrait, map: BTreeMap<String, String>) -> Self {
+        let v3_key = pay.v3_key();
+        let sign = crate::v2::sign(&map, &v3_key, SignType::HmacSha256);
+        let field = |name: &str| map.get(name).cloned().unwrap_or_default();
+        Self {
+            mch_id: field("mch_id"),
+            package: field("package"),
+            timestamp: field("timestamp"),
+            nonce_str: field("nonce_str"),
+            sign_type: field("sign_type"),
+            sign,
+        }
+    }
+
+    /// APP和JSAPI调起支付分时使用的query字符串
+    pub fn to_query_string(&self) -> String {
+        format!(
+            "mch_id={}&package={}&timestamp={}&nonce_str={}&sign_type={}&sign={}",
+            self.mch_id, self.package, self.timestamp, self.nonce_str, self.sign_type, self.sign
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::ParamsTrait;
+    use crate::money::Money;
+    use crate::pay::WechatPay;
+    use crate::payscore::{
+        CancelServiceOrderParams, ModifyServiceOrderParams, PayScoreExtraData, PostDiscount,
+        PostPayment, ServiceOrder, ServiceOrderAmountParams,
+    };
+    use crate::sign;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_extra_data() {
+        let wechat_pay = WechatPay::new(
+            "wxd678efh567hg6787",
+            "1230000109",
+            "",
+            "",
+            "0123456789abcdef0123456789abcdef",
+            "",
+        );
+        let mut map = BTreeMap::new();
+        for (name, value) in [
+            ("mch_id", "1230000109"),
+            ("package", "XXXXXXXX"),
+            ("timestamp", "1530097563"),
+            ("nonce_str", "zyx53Nkey8o4bHpxTQvd8m7e92nG5mG2"),
+            ("sign_type", "HMAC-SHA256"),
+        ] {
+            map.insert(name.to_string(), value.to_string());
+        }
+        let extra_data = PayScoreExtraData::sign(&wechat_pay, map);
+        let expected = sign::hmac_sha256_hex(
+            "0123456789abcdef0123456789abcdef",
+            "mch_id=1230000109&nonce_str=zyx53Nkey8o4bHpxTQvd8m7e92nG5mG2&package=XXXXXXXX\
+             &sign_type=HMAC-SHA256&timestamp=1530097563&key=0123456789abcdef0123456789abcdef",
+        );
+        assert_eq!(extra_data.sign, expected);
+        assert!(extra_data
+            .to_query_string()
+            .ends_with(&format!("&sign={}", expected)));
+    }
+
+    #[test]
+    fn test_service_order() {
+        let order: ServiceOrder = serde_json::from_str(
+            r#"{"appid":"wxd678efh567hg6787","mchid":"1230000109","out_order_no":"1234323JKHDFE1243252",
+            "service_id":"500001","state":"DONE","state_description":"MCH_COMPLETE","total_amount":40000,
+            "post_payments":[{"name":"就餐费用","amount":40000,"description":"就餐人均100元","count":4}],
+            "need_collection":true,"collection":{"state":"USER_PAID","total_amount":40000,"paying_amount":0,
+            "paid_amount":40000,"details":[{"seq":1,"amount":40000,"paid_type":"NEWTON",
+            "paid_time":"20091225091210","transaction_id":"15646546545165651651"}]},
+            "order_id":"15646546545165651651","openid":"oUpF8uMuAJO_M2pxb1Q9zNjWeS6o"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            order.collection.unwrap().paid_amount.unwrap().cents(),
+            40000
+        );
+
+        let params = ServiceOrderAmountParams::builder()
+            .service_id("500001")
+            .post_payments(vec![PostPayment {
+                name: "就餐费用".to_string(),
+                amount: Some(Money::from_cents(40000)),
+                description: None,
+                count: Some(4),
+            }])
+            .post_discounts(vec![PostDiscount {
+                name: "满20减1元".to_string(),
+                description: "不与其他优惠叠加".to_string(),
+                amount: Some(Money::from_cents(100)),
+                count: None,
+            }])
+            .total_amount(39900)
+            .build()
+            .unwrap();
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_amount_params_validation() {
+        let post_payments = vec![PostPayment {
+            name: "就餐费用".to_string(),
+            amount: Some(Money::from_cents(40000)),
+            description: None,
+            count: Some(4),
+        }];
+        let error = ServiceOrderAmountParams::builder()
+            .service_id("500001")
+            .post_payments(post_payments.clone())
+            .total_amount(39900)
+            .build()
+            .unwrap_err();
+        assert!(error.to_string().contains("total_amount"));
+
+        let error = ModifyServiceOrderParams::builder()
+            .service_id("500001")
+            .post_payments(post_payments.clone())
+            .total_amount(40000)
+            .build()
+            .unwrap_err();
+        assert!(error.to_string().contains("reason: is required"));
+        let mut params = ModifyServiceOrderParams::builder()
+            .service_id("500001")
+            .post_payments(post_payments)
+            .total_amount(40000)
+            .reason("用户投诉")
+            .build()
+            .unwrap();
+        params.total_amount = Money::from_cents(1);
+        assert!(params.validate().is_err());
+
+        assert!(CancelServiceOrderParams::new("500001", "")
+            .validate()
+            .is_err());
+    }
+}
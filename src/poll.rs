@@ -0,0 +1,97 @@
+use crate::error::PayError;
+use std::time::{Duration, Instant};
+
+/// 查询订单的退避间隔，每次等待时间增加一半，最多为初始间隔的4倍，不会超过截止时间
+pub(crate) struct Backoff {
+    interval: Duration,
+    max_interval: Duration,
+    deadline: Instant,
+}
+
+impl Backoff {
+    pub(crate) fn new(timeout: Duration, interval: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            interval,
+            max_interval: interval.saturating_mul(4),
+            deadline: now.checked_add(timeout).unwrap_or(now),
+        }
+    }
+
+    /// 下一次查询前需要等待的时间，已到截止时间时返回`None`
+    pub(crate) fn next_delay(&mut self) -> Option<Duration> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return None;
+        }
+        let delay = self.interval.min(remaining);
+        self.interval = (self.interval + self.interval / 2).min(self.max_interval);
+        Some(delay)
+    }
+}
+
+/// 撤销订单的最多尝试次数
+pub(crate) const REVERSE_ATTEMPTS: u32 = 3;
+
+/// 付款码支付轮询订单时，在截止时间前为撤销重试预留的时间
+pub(crate) fn reverse_reserve(interval: Duration) -> Duration {
+    interval.saturating_mul(REVERSE_ATTEMPTS - 1)
+}
+
+/// 网络超时、连接失败等请求错误，以及微信支付返回的系统错误、用户支付中，可以重试
+pub(crate) fn is_transient(err: &PayError) -> bool {
+    match err {
+        PayError::RequestError(e) => {
+            e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+        }
+        PayError::WechatError(message) => {
+            let code = message.split(':').next().unwrap_or_default();
+            matches!(code, "SYSTEM_ERROR" | "SYSTEMERROR" | "USERPAYING")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PayError;
+    use crate::poll::{is_transient, Backoff};
+    use crate::response::QueryOrderResponse;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(Duration::from_secs(60), Duration::from_secs(2));
+        let delays = (0..6)
+            .map(|_| backoff.next_delay().unwrap().as_secs_f32())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, vec![2.0, 3.0, 4.5, 6.75, 8.0, 8.0]);
+
+        let mut backoff = Backoff::new(Duration::from_millis(10), Duration::from_secs(2));
+        assert!(backoff.next_delay().unwrap() <= Duration::from_millis(10));
+        std::thread::sleep(Duration::from_millis(15));
+        assert!(backoff.next_delay().is_none());
+    }
+
+    #[test]
+    fn test_retryable() {
+        let response: QueryOrderResponse =
+            serde_json::from_str(r#"{"code":"ORDER_NOT_EXIST","message":"订单不存在"}"#).unwrap();
+        assert!(response.is_retryable());
+        let response: QueryOrderResponse =
+            serde_json::from_str(r#"{"code":"PARAM_ERROR","message":"参数错误"}"#).unwrap();
+        assert!(!response.is_retryable());
+        let response: QueryOrderResponse =
+            serde_json::from_str(r#"{"trade_state":"NOTPAY","code":"SYSTEM_ERROR"}"#).unwrap();
+        assert!(!response.is_retryable());
+        assert!(!is_transient(&PayError::WechatError(
+            "PARAM_ERROR: 参数错误".to_string()
+        )));
+        assert!(is_transient(&PayError::WechatError(
+            "SYSTEMERROR: 系统错误".to_string()
+        )));
+        assert!(is_transient(&PayError::WechatError(
+            "USERPAYING: 用户支付中".to_string()
+        )));
+    }
+}
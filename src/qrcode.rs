@@ -0,0 +1,288 @@
+//! 把Native支付返回的`code_url`渲染成二维码，支持PNG、SVG和终端字符三种输出，需要开启`qrcode`特性
+use crate::error::PayError;
+use crate::response::NativeResponse;
+
+/// 二维码纠错等级，等级越高可被遮挡的面积越大，二维码也越密
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcLevel {
+    /// 约7%
+    L,
+    /// 约15%
+    M,
+    /// 约25%
+    Q,
+    /// 约30%
+    H,
+}
+
+impl From<EcLevel> for ::qrcode::EcLevel {
+    fn from(value: EcLevel) -> Self {
+        match value {
+            EcLevel::L => ::qrcode::EcLevel::L,
+            EcLevel::M => ::qrcode::EcLevel::M,
+            EcLevel::Q => ::qrcode::EcLevel::Q,
+            EcLevel::H => ::qrcode::EcLevel::H,
+        }
+    }
+}
+
+/// 图片边长的上限（像素）
+const MAX_SIZE: u32 = 4096;
+/// 留白模块数的上限
+const MAX_MARGIN: u32 = 64;
+
+/// 二维码渲染参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QrOptions {
+    /// 图片的最小边长（像素），不超过4096，终端输出时忽略，默认256
+    pub size: u32,
+    /// 四周留白的模块数，不超过64，默认4
+    pub margin: u32,
+    /// 纠错等级，默认M
+    pub ec_level: EcLevel,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            size: 256,
+            margin: 4,
+            ec_level: EcLevel::M,
+        }
+    }
+}
+
+impl QrOptions {
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_ec_level(mut self, ec_level: EcLevel) -> Self {
+        self.ec_level = ec_level;
+        self
+    }
+}
+
+/// 包含留白的二维码模块矩阵，`true`为深色
+struct Modules {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+impl Modules {
+    fn new(content: &str, options: &QrOptions) -> Result<Self, PayError> {
+        if options.margin > MAX_MARGIN {
+            return Err(PayError::QrCodeError(format!(
+                "margin {} exceeds {}",
+                options.margin, MAX_MARGIN
+            )));
+        }
+        let code = ::qrcode::QrCode::with_error_correction_level(content, options.ec_level.into())
+            .map_err(|e| PayError::QrCodeError(e.to_string()))?;
+        let code_width = code.width();
+        let margin = options.margin as usize;
+        let width = code_width + margin * 2;
+        let colors = code.to_colors();
+        let mut dark = vec![false; width * width];
+        for y in 0..code_width {
+            for x in 0..code_width {
+                let color = colors[y * code_width + x];
+                dark[(y + margin) * width + x + margin] = color == ::qrcode::Color::Dark;
+            }
+        }
+        Ok(Self { width, dark })
+    }
+
+    fn is_dark(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.width && self.dark[y * self.width + x]
+    }
+
+    /// 每个模块的像素数和图片边长，保证边长不小于`size`
+    fn scale(&self, size: u32) -> Result<(usize, usize), PayError> {
+        if size > MAX_SIZE {
+            return Err(PayError::QrCodeError(format!(
+                "size {} exceeds {}",
+                size, MAX_SIZE
+            )));
+        }
+        let scale = (size as usize).div_ceil(self.width).max(1);
+        let dimension = self
+            .width
+            .checked_mul(scale)
+            .ok_or_else(|| PayError::QrCodeError(format!("size {} is too large", size)))?;
+        Ok((scale, dimension))
+    }
+}
+
+/// 渲染成灰度PNG图片
+pub fn render_png(content: &str, options: &QrOptions) -> Result<Vec<u8>, PayError> {
+    let modules = Modules::new(content, options)?;
+    let (scale, dimension) = modules.scale(options.size)?;
+    let area = dimension
+        .checked_mul(dimension)
+        .ok_or_else(|| PayError::QrCodeError(format!("size {} is too large", options.size)))?;
+    let mut pixels = Vec::with_capacity(area);
+    for y in 0..dimension {
+        for x in 0..dimension {
+            let dark = modules.is_dark(x / scale, y / scale);
+            pixels.push(if dark { 0u8 } else { 255u8 });
+        }
+    }
+    let mut buffer = Vec::new();
+    let dimension = u32::try_from(dimension).map_err(|e| PayError::QrCodeError(e.to_string()))?;
+    let mut encoder = png::Encoder::new(&mut buffer, dimension, dimension);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| PayError::QrCodeError(e.to_string()))?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| PayError::QrCodeError(e.to_string()))?;
+    writer
+        .finish()
+        .map_err(|e| PayError::QrCodeError(e.to_string()))?;
+    Ok(buffer)
+}
+
+/// 渲染成SVG字符串，深色模块合并为一个path
+pub fn render_svg(content: &str, options: &QrOptions) -> Result<String, PayError> {
+    let modules = Modules::new(content, options)?;
+    let (_, dimension) = modules.scale(options.size)?;
+    let mut path = String::new();
+    for y in 0..modules.width {
+        for x in 0..modules.width {
+            if modules.is_dark(x, y) {
+                path.push_str(&format!("M{},{}h1v1h-1z", x, y));
+            }
+        }
+    }
+    Ok(format!(
+        concat!(
+            r#"<?xml version="1.0" standalone="yes"?>"#,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" width="{size}" height="{size}" viewBox="0 0 {width} {width}" shape-rendering="crispEdges">"#,
+            r##"<rect width="{width}" height="{width}" fill="#fff"/>"##,
+            r##"<path fill="#000" d="{path}"/>"##,
+            "</svg>"
+        ),
+        size = dimension,
+        width = modules.width,
+        path = path
+    ))
+}
+
+/// 渲染成可以直接打印到终端的字符块，每个字符表示上下两个模块
+pub fn render_terminal(content: &str, options: &QrOptions) -> Result<String, PayError> {
+    let modules = Modules::new(content, options)?;
+    let mut output = String::new();
+    for y in (0..modules.width).step_by(2) {
+        for x in 0..modules.width {
+            let block = match (modules.is_dark(x, y), modules.is_dark(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            };
+            output.push(block);
+        }
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+impl NativeResponse {
+    fn code_url_or_error(&self) -> Result<&str, PayError> {
+        self.code_url.as_deref().ok_or_else(|| {
+            PayError::QrCodeError(format!(
+                "code_url is missing: {} {}",
+                self.code.as_deref().unwrap_or_default(),
+                self.message.as_deref().unwrap_or_default()
+            ))
+        })
+    }
+
+    /// 把`code_url`渲染成PNG图片
+    pub fn qrcode_png(&self, options: &QrOptions) -> Result<Vec<u8>, PayError> {
+        render_png(self.code_url_or_error()?, options)
+    }
+
+    /// 把`code_url`渲染成SVG字符串
+    pub fn qrcode_svg(&self, options: &QrOptions) -> Result<String, PayError> {
+        render_svg(self.code_url_or_error()?, options)
+    }
+
+    /// 把`code_url`渲染成终端字符块
+    pub fn qrcode_terminal(&self, options: &QrOptions) -> Result<String, PayError> {
+        render_terminal(self.code_url_or_error()?, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::qrcode::{EcLevel, Modules, QrOptions};
+    use crate::response::NativeResponse;
+
+    fn response() -> NativeResponse {
+        NativeResponse {
+            code: None,
+            message: None,
+            code_url: Some("weixin://wxpay/bizpayurl/up?pr=NwY5Mz9&groupid=00".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_png() {
+        let options = QrOptions::default().with_size(200).with_margin(2);
+        let png = response().qrcode_png(&options).unwrap();
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert!(info.width >= 200);
+        assert_eq!(info.width, info.height);
+        let code_url = response().code_url.unwrap();
+        let modules = Modules::new(&code_url, &options).unwrap();
+        let (scale, dimension) = modules.scale(200).unwrap();
+        assert_eq!(info.width as usize, dimension);
+        //左上角留白为白色，定位图案从留白后开始为黑色
+        assert_eq!(pixels[0], 255);
+        let finder = 2 * scale * info.width as usize + 2 * scale;
+        assert_eq!(pixels[finder], 0);
+    }
+
+    #[test]
+    fn test_render_svg_and_terminal() {
+        let options = QrOptions::default().with_ec_level(EcLevel::H);
+        let svg = response().qrcode_svg(&options).unwrap();
+        assert!(svg.contains("<svg") && svg.ends_with("</svg>"));
+
+        let options = QrOptions::default().with_margin(1);
+        let text = response().qrcode_terminal(&options).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        let width = lines[0].chars().count();
+        assert_eq!(lines.len(), width.div_ceil(2));
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+        //第一行为一行留白和定位图案的上边缘
+        assert!(lines[0].starts_with(" ▄▄▄▄▄▄▄"));
+
+        let empty = NativeResponse {
+            code: Some("PARAM_ERROR".to_string()),
+            message: Some("参数错误".to_string()),
+            code_url: None,
+        };
+        assert!(empty.qrcode_png(&QrOptions::default()).is_err());
+
+        assert!(response()
+            .qrcode_png(&QrOptions::default().with_size(u32::MAX))
+            .is_err());
+        assert!(response()
+            .qrcode_svg(&QrOptions::default().with_margin(u32::MAX))
+            .is_err());
+    }
+}
@@ -0,0 +1,421 @@
+use crate::bill::TradeBillRecord;
+use crate::error::PayError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// 本地订单状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LocalStatus {
+    ///支付成功 / 退款成功
+    Success,
+    ///处理中，例如未支付或退款处理中
+    Processing,
+    ///已关闭、已撤销或退款失败
+    Closed,
+}
+
+impl Display for LocalStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalStatus::Success => write!(f, "SUCCESS"),
+            LocalStatus::Processing => write!(f, "PROCESSING"),
+            LocalStatus::Closed => write!(f, "CLOSED"),
+        }
+    }
+}
+
+/// 本地的支付或退款记录，`out_refund_no`不为空时表示退款记录。金额单位为分。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalOrder {
+    ///【商户订单号】
+    pub out_trade_no: String,
+    ///【微信订单号】 本地已知时用于辅助匹配
+    pub transaction_id: Option<String>,
+    ///【商户退款单号】
+    pub out_refund_no: Option<String>,
+    ///【金额】 支付记录为订单金额，退款记录为申请退款金额
+    pub amount: i64,
+    ///【状态】
+    pub status: LocalStatus,
+}
+
+impl LocalOrder {
+    /// 支付记录
+    pub fn payment<S: AsRef<str>>(out_trade_no: S, amount: i64, status: LocalStatus) -> Self {
+        Self {
+            out_trade_no: out_trade_no.as_ref().to_string(),
+            transaction_id: None,
+            out_refund_no: None,
+            amount,
+            status,
+        }
+    }
+
+    /// 退款记录
+    pub fn refund<S: AsRef<str>>(
+        out_trade_no: S,
+        out_refund_no: S,
+        amount: i64,
+        status: LocalStatus,
+    ) -> Self {
+        Self {
+            out_trade_no: out_trade_no.as_ref().to_string(),
+            transaction_id: None,
+            out_refund_no: Some(out_refund_no.as_ref().to_string()),
+            amount,
+            status,
+        }
+    }
+
+    pub fn with_transaction_id<S: AsRef<str>>(mut self, transaction_id: S) -> Self {
+        self.transaction_id = Some(transaction_id.as_ref().to_string());
+        self
+    }
+}
+
+/// 对账结果类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReconcileKind {
+    ///金额、状态一致
+    Matched,
+    ///微信账单中存在，本地不存在
+    MissingLocally,
+    ///本地存在，微信账单中不存在
+    MissingRemotely,
+    ///金额不一致
+    AmountMismatch,
+    ///状态不一致
+    StatusMismatch,
+}
+
+impl Display for ReconcileKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReconcileKind::Matched => write!(f, "MATCHED"),
+            ReconcileKind::MissingLocally => write!(f, "MISSING_LOCALLY"),
+            ReconcileKind::MissingRemotely => write!(f, "MISSING_REMOTELY"),
+            ReconcileKind::AmountMismatch => write!(f, "AMOUNT_MISMATCH"),
+            ReconcileKind::StatusMismatch => write!(f, "STATUS_MISMATCH"),
+        }
+    }
+}
+
+/// 单条对账结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconcileEntry {
+    pub kind: ReconcileKind,
+    pub out_trade_no: String,
+    pub transaction_id: Option<String>,
+    pub out_refund_no: Option<String>,
+    pub local_amount: Option<i64>,
+    pub remote_amount: Option<i64>,
+    pub local_status: Option<LocalStatus>,
+    pub remote_status: Option<String>,
+}
+
+/// 对账报告
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    pub entries: Vec<ReconcileEntry>,
+}
+
+impl ReconcileReport {
+    /// 指定类型的对账结果
+    pub fn entries_of(&self, kind: ReconcileKind) -> impl Iterator<Item = &ReconcileEntry> {
+        self.entries.iter().filter(move |entry| entry.kind == kind)
+    }
+
+    /// 指定类型的对账结果数量
+    pub fn count(&self, kind: ReconcileKind) -> usize {
+        self.entries_of(kind).count()
+    }
+
+    /// 是否全部对平
+    pub fn is_balanced(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| entry.kind == ReconcileKind::Matched)
+    }
+
+    pub fn to_json(&self) -> Result<String, PayError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "kind,out_trade_no,transaction_id,out_refund_no,local_amount,remote_amount,local_status,remote_status\n",
+        );
+        for entry in &self.entries {
+            let fields = [
+                entry.kind.to_string(),
+                entry.out_trade_no.clone(),
+                entry.transaction_id.clone().unwrap_or_default(),
+                entry.out_refund_no.clone().unwrap_or_default(),
+                entry
+                    .local_amount
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                entry
+                    .remote_amount
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                entry
+                    .local_status
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                entry.remote_status.clone().unwrap_or_default(),
+            ];
+            let line = fields
+                .iter()
+                .map(|field| csv_escape(field))
+                .collect::<Vec<_>>()
+                .join(",");
+            csv.push_str(&line);
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RecordKey {
+    Payment(String),
+    Refund(String),
+}
+
+impl RecordKey {
+    fn of_local(order: &LocalOrder) -> Self {
+        match &order.out_refund_no {
+            Some(out_refund_no) => RecordKey::Refund(out_refund_no.clone()),
+            None => RecordKey::Payment(order.out_trade_no.clone()),
+        }
+    }
+
+    fn of_remote(record: &TradeBillRecord) -> Self {
+        match &record.out_refund_no {
+            Some(out_refund_no) if record.is_refund() => RecordKey::Refund(out_refund_no.clone()),
+            _ => RecordKey::Payment(record.out_trade_no.clone()),
+        }
+    }
+}
+
+/// 账单记录对应的金额：支付记录取订单金额，退款记录取申请退款金额
+fn remote_amount(record: &TradeBillRecord) -> i64 {
+    if record.is_refund() {
+        record
+            .apply_refund_amount
+            .or(record.refund_amount)
+            .unwrap_or_default()
+    } else {
+        record.order_amount.unwrap_or(record.settlement_total)
+    }
+}
+
+/// 账单记录对应的状态：支付记录取交易状态，退款记录取退款状态
+fn remote_status(record: &TradeBillRecord) -> String {
+    if record.is_refund() {
+        record
+            .refund_status
+            .clone()
+            .unwrap_or_else(|| record.trade_state.to_string())
+    } else {
+        record.trade_state.to_string()
+    }
+}
+
+fn status_matches(local: LocalStatus, remote: &str) -> bool {
+    match remote {
+        "SUCCESS" => local == LocalStatus::Success,
+        "PROCESSING" | "USERPAYING" | "NOTPAY" => local == LocalStatus::Processing,
+        _ => local == LocalStatus::Closed,
+    }
+}
+
+/// 以本地订单和微信交易账单进行对账。
+/// 支付记录以商户订单号匹配（本地有微信订单号时也会以微信订单号匹配），退款记录以商户退款单号匹配。
+/// ```rust
+/// use wechat_pay_rust_sdk::bill::TradeBillReader;
+/// use wechat_pay_rust_sdk::reconcile::{reconcile, LocalOrder, LocalStatus, ReconcileKind};
+///
+/// let bill = "交易时间,公众账号ID,商户号,特约商户号,设备号,微信订单号,商户订单号,用户标识,交易类型,交易状态,付款银行,货币种类,应结订单金额,代金券金额,商品名称,商户数据包,手续费,费率,订单金额,费率备注\n\
+/// `2024-01-12 10:36:13,`wx15f4803f25xxxxx,`1639718111,`0,`,`4200001926202401125681342683,`T001,`oAZUY6DittOj59wCzPn6vNgpK2eY,`MWEB,`SUCCESS,`OTHERS,`CNY,`0.01,`0.00,`测试支付1分,`,`0.00000,`0.60%,`0.01,`\n";
+/// let records = TradeBillReader::new(bill.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// let report = reconcile(vec![LocalOrder::payment("T001", 1, LocalStatus::Success)], records);
+/// assert_eq!(report.count(ReconcileKind::Matched), 1);
+/// ```
+pub fn reconcile<L, B>(local: L, bill: B) -> ReconcileReport
+where
+    L: IntoIterator<Item = LocalOrder>,
+    B: IntoIterator<Item = TradeBillRecord>,
+{
+    let mut remote: HashMap<RecordKey, TradeBillRecord> = HashMap::new();
+    let mut by_transaction_id: HashMap<String, RecordKey> = HashMap::new();
+    // 保留账单顺序，便于输出稳定的报告
+    let mut remote_order: Vec<RecordKey> = Vec::new();
+    for record in bill {
+        let key = RecordKey::of_remote(&record);
+        if let RecordKey::Payment(_) = key {
+            by_transaction_id.insert(record.transaction_id.clone(), key.clone());
+        }
+        if remote.insert(key.clone(), record).is_none() {
+            remote_order.push(key);
+        }
+    }
+
+    let mut report = ReconcileReport::default();
+    for order in local {
+        let mut key = RecordKey::of_local(&order);
+        if !remote.contains_key(&key) && order.out_refund_no.is_none() {
+            if let Some(found) = order
+                .transaction_id
+                .as_ref()
+                .and_then(|transaction_id| by_transaction_id.get(transaction_id))
+            {
+                key = found.clone();
+            }
+        }
+        let entry = match remote.remove(&key) {
+            Some(record) => {
+                let amount = remote_amount(&record);
+                let status = remote_status(&record);
+                let kind = if amount != order.amount {
+                    ReconcileKind::AmountMismatch
+                } else if !status_matches(order.status, &status) {
+                    ReconcileKind::StatusMismatch
+                } else {
+                    ReconcileKind::Matched
+                };
+                ReconcileEntry {
+                    kind,
+                    out_trade_no: order.out_trade_no,
+                    transaction_id: Some(record.transaction_id),
+                    out_refund_no: order.out_refund_no,
+                    local_amount: Some(order.amount),
+                    remote_amount: Some(amount),
+                    local_status: Some(order.status),
+                    remote_status: Some(status),
+                }
+            }
+            None => ReconcileEntry {
+                kind: ReconcileKind::MissingRemotely,
+                out_trade_no: order.out_trade_no,
+                transaction_id: order.transaction_id,
+                out_refund_no: order.out_refund_no,
+                local_amount: Some(order.amount),
+                remote_amount: None,
+                local_status: Some(order.status),
+                remote_status: None,
+            },
+        };
+        report.entries.push(entry);
+    }
+
+    for key in remote_order {
+        if let Some(record) = remote.remove(&key) {
+            report.entries.push(ReconcileEntry {
+                kind: ReconcileKind::MissingLocally,
+                remote_amount: Some(remote_amount(&record)),
+                remote_status: Some(remote_status(&record)),
+                out_trade_no: record.out_trade_no,
+                transaction_id: Some(record.transaction_id),
+                out_refund_no: record.out_refund_no,
+                local_amount: None,
+                local_status: None,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bill::{TradeBillReader, TradeBillRecord};
+    use crate::reconcile::{reconcile, LocalOrder, LocalStatus, ReconcileKind};
+
+    const TRADE_BILL: &str = "交易时间,公众账号ID,商户号,特约商户号,设备号,微信订单号,商户订单号,用户标识,交易类型,交易状态,付款银行,货币种类,应结订单金额,代金券金额,微信退款单号,商户退款单号,退款金额,充值券退款金额,退款类型,退款状态,商品名称,商户数据包,手续费,费率,订单金额,申请退款金额,费率备注
+`2024-01-12 10:00:00,`wx1,`1639718111,`0,`,`4200000000000000000000000001,`T001,`openid,`JSAPI,`SUCCESS,`OTHERS,`CNY,`1.00,`0.00,`0,`0,`0.00,`0.00,`,`,`商品,`,`0.01000,`0.60%,`1.00,`0.00,`
+`2024-01-12 10:00:01,`wx1,`1639718111,`0,`,`4200000000000000000000000002,`T002,`openid,`JSAPI,`SUCCESS,`OTHERS,`CNY,`2.00,`0.00,`0,`0,`0.00,`0.00,`,`,`商品,`,`0.01000,`0.60%,`2.00,`0.00,`
+`2024-01-12 10:00:02,`wx1,`1639718111,`0,`,`4200000000000000000000000003,`T003,`openid,`JSAPI,`SUCCESS,`OTHERS,`CNY,`3.00,`0.00,`0,`0,`0.00,`0.00,`,`,`商品,`,`0.02000,`0.60%,`3.00,`0.00,`
+`2024-01-12 10:00:03,`wx1,`1639718111,`0,`,`4200000000000000000000000004,`T004,`openid,`JSAPI,`SUCCESS,`OTHERS,`CNY,`4.00,`0.00,`0,`0,`0.00,`0.00,`,`,`商品,`,`0.02000,`0.60%,`4.00,`0.00,`
+`2024-01-12 11:00:00,`wx1,`1639718111,`0,`,`4200000000000000000000000001,`T001,`openid,`JSAPI,`REFUND,`OTHERS,`CNY,`0.00,`0.00,`50300000000000000000000001,`R001,`0.50,`0.00,`ORIGINAL,`SUCCESS,`商品,`,`0.00000,`0.60%,`0.00,`0.50,`
+";
+
+    fn records() -> Vec<TradeBillRecord> {
+        TradeBillReader::new(TRADE_BILL.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let local = vec![
+            LocalOrder::payment("T001", 100, LocalStatus::Success),
+            LocalOrder::payment("T002", 150, LocalStatus::Success),
+            LocalOrder::payment("T003", 300, LocalStatus::Processing),
+            LocalOrder::payment("T005", 500, LocalStatus::Success),
+            LocalOrder::payment("LOCAL-004", 400, LocalStatus::Success)
+                .with_transaction_id("4200000000000000000000000004"),
+            LocalOrder::refund("T001", "R001", 50, LocalStatus::Success),
+        ];
+        let report = reconcile(local, records());
+        let kinds = report
+            .entries
+            .iter()
+            .map(|entry| (entry.out_trade_no.as_str(), entry.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("T001", ReconcileKind::Matched),
+                ("T002", ReconcileKind::AmountMismatch),
+                ("T003", ReconcileKind::StatusMismatch),
+                ("T005", ReconcileKind::MissingRemotely),
+                ("LOCAL-004", ReconcileKind::Matched),
+                ("T001", ReconcileKind::Matched),
+            ]
+        );
+        assert!(!report.is_balanced());
+        assert_eq!(report.count(ReconcileKind::Matched), 3);
+    }
+
+    #[test]
+    fn test_missing_locally() {
+        let report = reconcile(vec![], records());
+        assert_eq!(report.count(ReconcileKind::MissingLocally), 5);
+        let refund = report
+            .entries
+            .iter()
+            .find(|entry| entry.out_refund_no.is_some())
+            .unwrap();
+        assert_eq!(refund.remote_amount, Some(50));
+        assert_eq!(refund.remote_status.as_deref(), Some("SUCCESS"));
+    }
+
+    #[test]
+    fn test_report_output() {
+        let report = reconcile(
+            vec![LocalOrder::payment("T,9", 1, LocalStatus::Success)],
+            vec![],
+        );
+        let csv = report.to_csv();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("MISSING_REMOTELY,\"T,9\",,,1,,SUCCESS,")
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["entries"][0]["kind"], "MISSING_REMOTELY");
+    }
+}
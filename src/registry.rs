@@ -0,0 +1,390 @@
+use crate::config::WechatPayConfig;
+use crate::error::{ConfigError, PayError};
+use crate::model::{WechatPayDecodeData, WechatPayNotify};
+use crate::pay::{HttpClient, PayNotifyTrait, WechatPay, WechatPayTrait};
+use crate::response::CertificateResponse;
+use crate::util;
+use std::collections::HashMap;
+
+struct Merchant {
+    wechat_pay: WechatPay,
+    ///平台证书序列号 -> 平台证书公钥
+    platform_certificates: HashMap<String, String>,
+}
+
+/// 多商户管理，按商户号保存配置和平台证书。
+/// 未设置HTTP客户端的商户共用一个客户端，配置了商户API证书等自定义客户端的商户保留自己的客户端
+/// ```rust,no_run
+/// use wechat_pay_rust_sdk::config::WechatPayConfig;
+/// use wechat_pay_rust_sdk::registry::MerchantRegistry;
+///
+/// let mut registry = MerchantRegistry::new();
+/// registry
+///     .register_config(WechatPayConfig::from_file("1230000109.toml").unwrap())
+///     .unwrap();
+/// registry
+///     .add_platform_certificate("1230000109", "5157F09EFDC096DE15EBE81A47057A7232F1B8E1", "-----BEGIN CERTIFICATE-----...")
+///     .unwrap();
+/// let wechat_pay = registry.merchant("1230000109").unwrap();
+/// ```
+pub struct MerchantRegistry {
+    client: HttpClient,
+    merchants: HashMap<String, Merchant>,
+}
+
+impl Default for MerchantRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MerchantRegistry {
+    pub fn new() -> Self {
+        Self::with_client(HttpClient::new())
+    }
+
+    pub fn with_client(client: HttpClient) -> Self {
+        Self {
+            client,
+            merchants: HashMap::new(),
+        }
+    }
+
+    /// 添加商户，商户号已存在时替换原有配置和平台证书。
+    /// 商户已有HTTP客户端（例如配置了`cert_path`的双向认证客户端）时不会被替换
+    pub fn register(&mut self, wechat_pay: WechatPay) -> &mut Self {
+        let wechat_pay = if wechat_pay.has_client() {
+            wechat_pay
+        } else {
+            wechat_pay.with_client(self.client.clone())
+        };
+        self.merchants.insert(
+            wechat_pay.mch_id(),
+            Merchant {
+                wechat_pay,
+                platform_certificates: HashMap::new(),
+            },
+        );
+        self
+    }
+
+    pub fn register_config(&mut self, config: WechatPayConfig) -> Result<&mut Self, ConfigError> {
+        Ok(self.register(config.build()?))
+    }
+
+    pub fn remove(&mut self, mch_id: &str) -> Option<WechatPay> {
+        self.merchants
+            .remove(mch_id)
+            .map(|merchant| merchant.wechat_pay)
+    }
+
+    pub fn get(&self, mch_id: &str) -> Option<&WechatPay> {
+        self.merchants
+            .get(mch_id)
+            .map(|merchant| &merchant.wechat_pay)
+    }
+
+    /// 按商户号获取商户，用于发起请求
+    pub fn merchant(&self, mch_id: &str) -> Result<&WechatPay, PayError> {
+        self.get(mch_id)
+            .ok_or_else(|| PayError::MerchantNotFound(mch_id.to_string()))
+    }
+
+    pub fn mch_ids(&self) -> impl Iterator<Item = &str> {
+        self.merchants.keys().map(|mch_id| mch_id.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.merchants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.merchants.is_empty()
+    }
+
+    /// 添加平台证书，`pem`可以是平台证书或证书公钥
+    pub fn add_platform_certificate(
+        &mut self,
+        mch_id: &str,
+        serial_no: &str,
+        pem: &str,
+    ) -> Result<(), PayError> {
+        let public_key = if pem.contains("BEGIN CERTIFICATE") {
+            util::x509_to_pem(pem.as_bytes())
+                .map_err(|e| PayError::VerifyError(format!("certificate parser error: {}", e)))?
+        } else {
+            pem.to_string()
+        };
+        self.merchants
+            .get_mut(mch_id)
+            .ok_or_else(|| PayError::MerchantNotFound(mch_id.to_string()))?
+            .platform_certificates
+            .insert(serial_no.to_string(), public_key);
+        Ok(())
+    }
+
+    /// 平台证书公钥
+    pub fn platform_public_key(&self, mch_id: &str, serial_no: &str) -> Option<&str> {
+        self.merchants
+            .get(mch_id)
+            .and_then(|merchant| merchant.platform_certificates.get(serial_no))
+            .map(|public_key| public_key.as_str())
+    }
+
+    /// 验证通知签名并解密通知内容。
+    /// 根据请求头`Wechatpay-Serial`找到持有该平台证书的商户，使用其平台证书验签、APIv3密钥解密，
+    /// 解密结果中的`mchid`与商户号不一致时继续尝试其他商户
+    pub fn decrypt_notify_bytes<S: AsRef<str>>(
+        &self,
+        serial: S,
+        timestamp: S,
+        nonce: S,
+        signature: S,
+        body: S,
+    ) -> Result<(&WechatPay, Vec<u8>), PayError> {
+        let serial = serial.as_ref();
+        let candidates = self
+            .merchants
+            .values()
+            .filter_map(|merchant| {
+                merchant
+                    .platform_certificates
+                    .get(serial)
+                    .map(|public_key| (&merchant.wechat_pay, public_key.as_str()))
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Err(PayError::VerifyError(format!(
+                "platform certificate not found: {}",
+                serial
+            )));
+        }
+        let notify: WechatPayNotify = serde_json::from_str(body.as_ref())?;
+        let resource = &notify.resource;
+        let associated_data = resource.associated_data.clone().unwrap_or_default();
+        let mut last_error = None;
+        for (wechat_pay, public_key) in candidates {
+            let result = wechat_pay
+                .verify_signature(
+                    public_key,
+                    timestamp.as_ref(),
+                    nonce.as_ref(),
+                    signature.as_ref(),
+                    body.as_ref(),
+                )
+                .and_then(|_| {
+                    wechat_pay.decrypt_bytes(
+                        resource.ciphertext.as_str(),
+                        resource.nonce.as_str(),
+                        associated_data.as_str(),
+                    )
+                });
+            match result {
+                Ok(data) if belongs_to(wechat_pay, &data) => return Ok((wechat_pay, data)),
+                Ok(_) => {
+                    last_error = Some(PayError::DecryptError(format!(
+                        "notify does not belong to merchant {}",
+                        wechat_pay.mch_id
+                    )))
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// 验证并解密支付通知
+    pub fn decrypt_notify<S: AsRef<str>>(
+        &self,
+        serial: S,
+        timestamp: S,
+        nonce: S,
+        signature: S,
+        body: S,
+    ) -> Result<(&WechatPay, WechatPayDecodeData), PayError> {
+        let (wechat_pay, data) =
+            self.decrypt_notify_bytes(serial, timestamp, nonce, signature, body)?;
+        Ok((wechat_pay, serde_json::from_slice(&data)?))
+    }
+
+    pub(crate) fn set_platform_certificates(
+        &mut self,
+        mch_id: &str,
+        certificates: Vec<(String, String)>,
+    ) -> Result<usize, PayError> {
+        let merchant = self
+            .merchants
+            .get_mut(mch_id)
+            .ok_or_else(|| PayError::MerchantNotFound(mch_id.to_string()))?;
+        let count = certificates.len();
+        merchant.platform_certificates.extend(certificates);
+        Ok(count)
+    }
+}
+
+/// 通知内容中的商户号（服务商模式为`sp_mchid`）与商户一致，不包含商户号时视为一致
+fn belongs_to(wechat_pay: &WechatPay, data: &[u8]) -> bool {
+    let value = match serde_json::from_slice::<serde_json::Value>(data) {
+        Ok(value) => value,
+        Err(_) => return true,
+    };
+    ["sp_mchid", "mchid"]
+        .iter()
+        .find_map(|key| value.get(key).and_then(|mch_id| mch_id.as_str()))
+        .map(|mch_id| mch_id == wechat_pay.mch_id)
+        .unwrap_or(true)
+}
+
+/// 解密平台证书列表，返回(证书序列号, 证书公钥)
+pub(crate) fn decode_certificates(
+    wechat_pay: &WechatPay,
+    response: CertificateResponse,
+) -> Result<Vec<(String, String)>, PayError> {
+    response
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .map(|certificate| {
+            let encrypt = certificate.encrypt_certificate;
+            let data = wechat_pay.decrypt_bytes(
+                encrypt.ciphertext,
+                encrypt.nonce,
+                encrypt.associated_data,
+            )?;
+            let public_key = util::x509_to_pem(data.as_slice())
+                .map_err(|e| PayError::VerifyError(format!("certificate parser error: {}", e)))?;
+            Ok((certificate.serial_no, public_key))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PayError;
+    use crate::pay::WechatPay;
+    use crate::registry::MerchantRegistry;
+    use crate::{sign, util};
+    use aes_gcm::aead::{Aead, Payload};
+    use aes_gcm::{Aes256Gcm, KeyInit};
+
+    const PRIVATE_KEY: &str = include_str!("../testdata/apiclient_key.pem");
+    const CERTIFICATE: &str = include_str!("../testdata/apiclient_cert.pem");
+    const SERIAL_NO: &str = "5157F09EFDC096DE15EBE81A47057A7232F1B8E1";
+
+    fn merchant(mch_id: &str, v3_key: &str) -> WechatPay {
+        WechatPay::new(
+            "wxd678efh567hg6787",
+            mch_id,
+            PRIVATE_KEY,
+            SERIAL_NO,
+            v3_key,
+            "https://mydomain.com/pay/notify",
+        )
+    }
+
+    /// 模拟微信支付平台发出的通知，返回(时间戳, 随机串, 签名, 通知内容)
+    fn notify(v3_key: &str, mch_id: &str) -> (String, String, String, String) {
+        let plaintext = format!(
+            r#"{{"mchid":"{}","appid":"wxd678efh567hg6787","out_trade_no":"1217752501201407033233368018","transaction_id":"4200001926202401125681342683","trade_type":"NATIVE","trade_state":"SUCCESS","trade_state_desc":"支付成功","payer":{{"openid":"oAZUY6DittOj59wCzPn6vNgpK2eY"}},"amount":{{"total":1}}}}"#,
+            mch_id
+        );
+        let cipher = Aes256Gcm::new(v3_key.as_bytes().into());
+        let ciphertext = cipher
+            .encrypt(
+                "uaGeNnBYNjl7".as_bytes().into(),
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: b"transaction",
+                },
+            )
+            .unwrap();
+        let body = format!(
+            r#"{{"id":"29a61973-babf-599a-966d-6bcdcf17360c","create_time":"2024-01-12T21:39:44+08:00","resource_type":"encrypt-resource","event_type":"TRANSACTION.SUCCESS","summary":"支付成功","resource":{{"original_type":"transaction","algorithm":"AEAD_AES_256_GCM","ciphertext":"{}","associated_data":"transaction","nonce":"uaGeNnBYNjl7"}}}}"#,
+            util::base64_encode(ciphertext)
+        );
+        let timestamp = "1705066785".to_string();
+        let nonce = "Jh9oPZelCJIQeQ47kz4stzvDKpLEUhCX".to_string();
+        let message = format!("{}\n{}\n{}\n", timestamp, nonce, body);
+        let signature = sign::sha256_sign(PRIVATE_KEY, message.as_str());
+        (timestamp, nonce, signature, body)
+    }
+
+    fn registry() -> MerchantRegistry {
+        let mut registry = MerchantRegistry::new();
+        registry
+            .register(merchant("1230000109", "0123456789abcdef0123456789abcdef"))
+            .register(merchant("1900000001", "abcdef0123456789abcdef0123456789"));
+        for mch_id in ["1230000109", "1900000001"] {
+            registry
+                .add_platform_certificate(mch_id, SERIAL_NO, CERTIFICATE)
+                .unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn test_route() {
+        let registry = registry();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.merchant("1900000001").unwrap().mch_id,
+            "1900000001"
+        );
+        assert!(matches!(
+            registry.merchant("1000000000"),
+            Err(PayError::MerchantNotFound(_))
+        ));
+        assert!(registry
+            .platform_public_key("1230000109", SERIAL_NO)
+            .unwrap()
+            .starts_with("-----BEGIN PUBLIC KEY-----"));
+    }
+
+    #[test]
+    fn test_decrypt_notify() {
+        let registry = registry();
+        let (timestamp, nonce, signature, body) =
+            notify("abcdef0123456789abcdef0123456789", "1900000001");
+        let (wechat_pay, data) = registry
+            .decrypt_notify(SERIAL_NO, &timestamp, &nonce, &signature, &body)
+            .unwrap();
+        assert_eq!(wechat_pay.mch_id, "1900000001");
+        assert_eq!(data.mchid, "1900000001");
+
+        let error = registry
+            .decrypt_notify("0000", &timestamp, &nonce, &signature, &body)
+            .unwrap_err();
+        assert!(matches!(error, PayError::VerifyError(_)));
+        let error = registry
+            .decrypt_notify(SERIAL_NO, "1705066786", &nonce, &signature, &body)
+            .unwrap_err();
+        assert!(matches!(error, PayError::VerifyError(_)));
+    }
+
+    #[test]
+    fn test_register_keeps_identity_client() {
+        use crate::config::WechatPayConfig;
+        use crate::pay::HttpClient;
+
+        let config = WechatPayConfig {
+            appid: Some("wxd678efh567hg6787".to_string()),
+            mch_id: Some("1230000109".to_string()),
+            private_key_path: Some("testdata/apiclient_key.pem".into()),
+            cert_path: Some("testdata/apiclient_cert.pem".into()),
+            v3_key: Some("0123456789abcdef0123456789abcdef".into()),
+            ..Default::default()
+        };
+        let identity_merchant = config.build().unwrap();
+        assert!(identity_merchant.has_client());
+        let plain_merchant = merchant("1900000001", "abcdef0123456789abcdef0123456789");
+        assert!(!plain_merchant.has_client());
+
+        let shared = HttpClient::builder().build().unwrap();
+        let mut registry = MerchantRegistry::with_client(shared);
+        registry
+            .register(identity_merchant)
+            .register(plain_merchant);
+        assert!(registry.get("1230000109").unwrap().has_client());
+        assert!(registry.get("1900000001").unwrap().has_client());
+    }
+}
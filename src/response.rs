@@ -1,3 +1,9 @@
+use crate::datetime;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::error::PayError;
+use crate::model::{AmountInfo, PayerInfo, PromotionDetail, TransactionSceneInfo};
+use crate::pay_type::{BankType, PayType, TradeState};
+use chrono::{DateTime, FixedOffset};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
@@ -81,8 +87,10 @@ pub struct EncryptCertificate {
 #[derive(Debug, Clone, Deserialize)]
 pub struct Certificate {
     pub serial_no: String,
-    pub effective_time: String,
-    pub expire_time: String,
+    #[serde(with = "datetime::rfc3339")]
+    pub effective_time: DateTime<FixedOffset>,
+    #[serde(with = "datetime::rfc3339")]
+    pub expire_time: DateTime<FixedOffset>,
     pub encrypt_certificate: EncryptCertificate,
 }
 
@@ -115,7 +123,8 @@ pub struct TransferBillsResponse {
     // 【微信转账单号】 微信转账单号，微信商家转账系统返回的唯一标识
     pub transfer_bill_no: String,
     // 【单据创建时间】 单据受理成功时返回，按照使用rfc3339所定义的格式，格式为yyyy-MM-DDThh:mm:ss+TIMEZONE
-    pub create_time: String,
+    #[serde(with = "datetime::rfc3339")]
+    pub create_time: DateTime<FixedOffset>,
     // 【单据状态】 商家转账订单状态
     pub state: String,
     // 【失败原因】 订单已失败或者已退资金时，会返回订单失败原因
@@ -125,3 +134,96 @@ pub struct TransferBillsResponse {
 }
 
 impl ResponseTrait for TransferBillsResponse {}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryOrderResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【公众账号ID】
+    pub appid: Option<String>,
+    ///【商户号】
+    pub mchid: Option<String>,
+    ///【商户订单号】 商户系统内部订单号
+    pub out_trade_no: Option<String>,
+    ///【微信支付订单号】 微信支付系统生成的订单号
+    pub transaction_id: Option<String>,
+    ///【交易类型】
+    pub trade_type: Option<PayType>,
+    ///【交易状态】
+    pub trade_state: Option<TradeState>,
+    ///【交易状态描述】
+    pub trade_state_desc: Option<String>,
+    ///【银行类型】
+    pub bank_type: Option<BankType>,
+    ///【附加数据】
+    pub attach: Option<String>,
+    ///【支付完成时间】
+    #[serde(default, with = "datetime::rfc3339_option")]
+    pub success_time: Option<DateTime<FixedOffset>>,
+    ///【支付者】
+    pub payer: Option<PayerInfo>,
+    ///【订单金额】
+    pub amount: Option<AmountInfo>,
+    ///【场景信息】
+    pub scene_info: Option<TransactionSceneInfo>,
+    ///【优惠功能】
+    pub promotion_detail: Option<Vec<PromotionDetail>>,
+}
+
+impl ResponseTrait for QueryOrderResponse {}
+
+/// 付款码支付的返回内容与查询订单相同
+pub type CodepayResponse = QueryOrderResponse;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl QueryOrderResponse {
+    /// 交易状态，查询失败时返回微信支付的错误码和错误信息
+    pub(crate) fn into_trade_state(self) -> Result<TradeState, PayError> {
+        self.trade_state.ok_or_else(|| {
+            PayError::WechatError(format!(
+                "{}: {}",
+                self.code.unwrap_or_default(),
+                self.message.unwrap_or_default()
+            ))
+        })
+    }
+
+    /// 查询失败但可以稍后重试，例如刚下单时订单尚未生成或微信支付系统繁忙
+    pub(crate) fn is_retryable(&self) -> bool {
+        self.trade_state.is_none()
+            && matches!(
+                self.code.as_deref(),
+                Some(
+                    "ORDER_NOT_EXIST"
+                        | "ORDERNOTEXIST"
+                        | "SYSTEM_ERROR"
+                        | "SYSTEMERROR"
+                        | "FREQUENCY_LIMITED"
+                )
+            )
+    }
+
+    /// 付款码支付的交易状态，返回`None`表示支付结果未知，需要查询订单确认。
+    /// 用户支付中、系统错误和银行错误的结果都不确定，其他错误说明支付失败
+    pub(crate) fn codepay_trade_state(self) -> Result<Option<TradeState>, PayError> {
+        if let Some(trade_state) = self.trade_state {
+            return Ok(Some(trade_state));
+        }
+        match self.code.as_deref() {
+            Some("USERPAYING" | "SYSTEM_ERROR" | "SYSTEMERROR" | "BANK_ERROR" | "BANKERROR") => {
+                Ok(None)
+            }
+            _ => self.into_trade_state().map(Some),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaUploadResponse {
+    pub code: Option<String>,
+    pub message: Option<String>,
+    ///【媒体文件标识Id】 用于其他接口引用上传的图片或视频，有效期为30天
+    pub media_id: Option<String>,
+}
+
+impl ResponseTrait for MediaUploadResponse {}
@@ -1,3 +1,4 @@
+use crate::error::PayError;
 use crate::util;
 use rsa::pkcs8::DecodePrivateKey;
 use rsa::sha2::Digest;
@@ -22,3 +23,100 @@ where
     let sign_result = private_key.sign(padding, &hasher).expect("failed to sign");
     util::base64_encode(sign_result)
 }
+
+/// MD5签名，返回大写十六进制字符串
+pub(crate) fn md5_hex(content: &str) -> String {
+    format!("{:X}", md5::compute(content))
+}
+
+/// SHA-1摘要，返回小写十六进制字符串，用于校验下载文件的hash_value
+pub(crate) fn sha1_hex(content: &[u8]) -> String {
+    hex(&sha1::Sha1::digest(content))
+}
+
+/// SHA-256摘要，返回小写十六进制字符串
+pub(crate) fn sha256_hex(content: &[u8]) -> String {
+    hex(&rsa::sha2::Sha256::digest(content))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// HMAC-SHA256签名，返回大写十六进制字符串
+pub(crate) fn hmac_sha256_hex(key: &str, content: &str) -> String {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<rsa::sha2::Sha256>::new_from_slice(key.as_bytes())
+        .expect("hmac accepts keys of any length");
+    mac.update(content.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+/// RSAES-OAEP（SHA-1）解密，密文为base64编码
+pub(crate) fn rsa_oaep_decrypt(private_key: &str, ciphertext: &str) -> Result<String, PayError> {
+    let private_key = RsaPrivateKey::from_pkcs8_pem(private_key)
+        .map_err(|e| PayError::DecryptError(format!("private key parser error: {}", e)))?;
+    let ciphertext = util::base64_decode(ciphertext)?;
+    let plaintext = private_key
+        .decrypt(rsa::Oaep::new::<sha1::Sha1>(), &ciphertext)
+        .map_err(|e| PayError::DecryptError(e.to_string()))?;
+    String::from_utf8(plaintext).map_err(|e| PayError::DecryptError(e.to_string()))
+}
+
+/// RSAES-OAEP（SHA-1）加密，公钥为PEM格式，返回base64编码的密文
+pub(crate) fn rsa_oaep_encrypt(public_key: &str, plaintext: &str) -> Result<String, PayError> {
+    use rsa::pkcs8::DecodePublicKey;
+    let public_key = rsa::RsaPublicKey::from_public_key_pem(public_key)
+        .map_err(|e| PayError::CertificateError(format!("public key parser error: {}", e)))?;
+    let ciphertext = public_key
+        .encrypt(
+            &mut rsa::rand_core::OsRng,
+            rsa::Oaep::new::<sha1::Sha1>(),
+            plaintext.as_bytes(),
+        )
+        .map_err(|e| PayError::CertificateError(e.to_string()))?;
+    Ok(util::base64_encode(ciphertext))
+}
+
+/// 微信支付平台证书（或平台公钥）及其序列号，用于加密请求中的敏感信息
+#[derive(Debug, Clone)]
+pub struct PlatformPublicKey {
+    serial_no: String,
+    public_key: String,
+}
+
+impl PlatformPublicKey {
+    /// 使用平台公钥，`serial_no`为平台公钥ID或平台证书序列号
+    pub fn new(serial_no: impl AsRef<str>, public_key_pem: impl AsRef<str>) -> Self {
+        Self {
+            serial_no: serial_no.as_ref().to_string(),
+            public_key: public_key_pem.as_ref().to_string(),
+        }
+    }
+
+    /// 从下载的PEM格式平台证书中读取公钥和序列号
+    pub fn from_certificate(certificate_pem: impl AsRef<[u8]>) -> Result<Self, PayError> {
+        let certificate_pem = certificate_pem.as_ref();
+        let serial_no = util::x509_serial_no(certificate_pem)
+            .map_err(|e| PayError::CertificateError(e.to_string()))?;
+        let public_key = util::x509_to_pem(certificate_pem)
+            .map_err(|e| PayError::CertificateError(e.to_string()))?;
+        Ok(Self {
+            serial_no,
+            public_key,
+        })
+    }
+
+    pub fn serial_no(&self) -> &str {
+        &self.serial_no
+    }
+
+    /// 加密敏感信息，例如姓名、证件号码、手机号、银行账号
+    pub fn encrypt(&self, plaintext: impl AsRef<str>) -> Result<String, PayError> {
+        rsa_oaep_encrypt(&self.public_key, plaintext.as_ref())
+    }
+}
@@ -0,0 +1,95 @@
+//! 商户API证书双向认证，退款、现金红包等接口需要在TLS握手时出示商户API证书
+use crate::error::PayError;
+use crate::pay::{HttpClient, WechatPay};
+use crate::v2::WechatPayV2;
+use reqwest::Identity;
+use std::path::Path;
+
+fn read(path: &Path) -> Result<Vec<u8>, PayError> {
+    std::fs::read(path)
+        .map_err(|e| PayError::CertificateError(format!("read {} error: {}", path.display(), e)))
+}
+
+/// 使用商户API证书apiclient_cert.pem和私钥apiclient_key.pem（PKCS#8）创建客户端身份
+pub fn identity_from_pem(cert: &[u8], key: &[u8]) -> Result<Identity, PayError> {
+    Ok(Identity::from_pkcs8_pem(cert, key)?)
+}
+
+pub fn identity_from_pem_files(
+    cert_path: impl AsRef<Path>,
+    key_path: impl AsRef<Path>,
+) -> Result<Identity, PayError> {
+    identity_from_pem(&read(cert_path.as_ref())?, &read(key_path.as_ref())?)
+}
+
+/// 使用PKCS#12证书apiclient_cert.p12创建客户端身份，证书密码为商户号
+pub fn identity_from_pkcs12(der: &[u8], mch_id: &str) -> Result<Identity, PayError> {
+    Ok(Identity::from_pkcs12_der(der, mch_id)?)
+}
+
+pub fn identity_from_pkcs12_file(
+    path: impl AsRef<Path>,
+    mch_id: &str,
+) -> Result<Identity, PayError> {
+    identity_from_pkcs12(&read(path.as_ref())?, mch_id)
+}
+
+/// 创建出示商户API证书的HTTP客户端
+pub fn client_with_identity(identity: Identity) -> Result<HttpClient, PayError> {
+    Ok(HttpClient::builder().identity(identity).build()?)
+}
+
+impl WechatPay {
+    /// 使用商户API证书进行双向认证
+    pub fn with_identity(self, identity: Identity) -> Result<Self, PayError> {
+        Ok(self.with_client(client_with_identity(identity)?))
+    }
+}
+
+impl WechatPayV2 {
+    /// 使用商户API证书进行双向认证，现金红包等接口必须设置
+    pub fn with_identity(self, identity: Identity) -> Result<Self, PayError> {
+        Ok(self.with_client(client_with_identity(identity)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pay::WechatPay;
+    use crate::tls;
+    use crate::util;
+
+    const CERT: &str = include_str!("../testdata/apiclient_cert.pem");
+    const PRIVATE_KEY: &str = include_str!("../testdata/apiclient_key.pem");
+    const PKCS12: &[u8] = include_bytes!("../testdata/apiclient_cert.p12");
+
+    #[test]
+    fn test_identity() {
+        let identity = tls::identity_from_pem(CERT.as_bytes(), PRIVATE_KEY.as_bytes()).unwrap();
+        let wechat_pay = WechatPay::new(
+            "appid",
+            "1230000109",
+            PRIVATE_KEY,
+            "serial_no",
+            "v3_key",
+            "",
+        )
+        .with_identity(identity);
+        assert!(wechat_pay.is_ok());
+        assert!(tls::identity_from_pkcs12(PKCS12, "1230000109").is_ok());
+        assert!(tls::identity_from_pkcs12(PKCS12, "wrong password").is_err());
+        assert!(tls::identity_from_pem_files(
+            "testdata/not_found.pem",
+            "testdata/apiclient_key.pem"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_serial_no() {
+        assert_eq!(
+            util::x509_serial_no(CERT.as_bytes()).unwrap(),
+            "5157F09EFDC096DE15EBE81A47057A7232F1B8E1"
+        );
+    }
+}
@@ -34,6 +34,23 @@ pub fn x509_to_pem(content: &[u8]) -> Result<String, Box<dyn Error>> {
     ))
 }
 
+/// 读取PEM证书的序列号，即商户API证书序列号`serial_no`，大写十六进制
+pub fn x509_serial_no(content: &[u8]) -> Result<String, Box<dyn Error>> {
+    let pem = pem::parse(content)?;
+    x509_serial_no_der(pem.contents())
+}
+
+/// 读取DER证书的序列号
+pub fn x509_serial_no_der(content: &[u8]) -> Result<String, Box<dyn Error>> {
+    let (_, cert) = x509_parser::parse_x509_certificate(content)?;
+    Ok(cert
+        .raw_serial()
+        .iter()
+        .skip_while(|b| **b == 0)
+        .map(|b| format!("{:02X}", b))
+        .collect())
+}
+
 pub fn x509_is_valid(content: &[u8]) -> Result<(bool, i64), Box<dyn Error>> {
     let pem = pem::parse(content)?;
     let (_, cert) = x509_parser::parse_x509_certificate(pem.contents())?;
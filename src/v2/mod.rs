@@ -0,0 +1,241 @@
+//! APIv2接口，XML报文，使用APIv2密钥进行MD5或HMAC-SHA256签名。
+//! 现金红包等只有APIv2的接口需要商户API证书，使用`with_client`传入配置了证书的HTTP客户端
+pub mod model;
+pub mod xml;
+
+use crate::error::PayError;
+use crate::pay::HttpClient;
+use crate::secret::SecretString;
+use crate::sign;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::OnceLock;
+
+/// APIv2签名类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignType {
+    #[default]
+    Md5,
+    HmacSha256,
+}
+
+impl SignType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SignType::Md5 => "MD5",
+            SignType::HmacSha256 => "HMAC-SHA256",
+        }
+    }
+}
+
+/// 生成APIv2签名：非空参数按字段名ASCII排序拼接为`k1=v1&k2=v2`，再拼接`&key=APIv2密钥`，
+/// 使用MD5或HMAC-SHA256计算后转为大写
+pub fn sign(params: &BTreeMap<String, String>, key: &str, sign_type: SignType) -> String {
+    let mut content = params
+        .iter()
+        .filter(|(name, value)| name.as_str() != "sign" && !value.is_empty())
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&");
+    content.push_str("&key=");
+    content.push_str(key);
+    match sign_type {
+        SignType::Md5 => sign::md5_hex(&content),
+        SignType::HmacSha256 => sign::hmac_sha256_hex(key, &content),
+    }
+}
+
+/// 校验响应或通知中的`sign`字段
+pub fn verify(
+    params: &BTreeMap<String, String>,
+    key: &str,
+    sign_type: SignType,
+) -> Result<(), PayError> {
+    let expected = params
+        .get("sign")
+        .ok_or_else(|| PayError::VerifyError("sign is missing".to_string()))?;
+    let sign_type = match params.get("sign_type").map(String::as_str) {
+        Some("HMAC-SHA256") => SignType::HmacSha256,
+        Some("MD5") => SignType::Md5,
+        _ => sign_type,
+    };
+    if &sign(params, key, sign_type) != expected {
+        return Err(PayError::VerifyError("sign mismatch".to_string()));
+    }
+    Ok(())
+}
+
+/// APIv2请求参数，发送前使用商户配置补全appid和商户号
+pub trait V2ParamsTrait: serde::Serialize {
+    fn fill_defaults(&mut self, _pay: &WechatPayV2) {}
+}
+
+/// APIv2客户端
+pub struct WechatPayV2 {
+    pub appid: String,
+    pub mch_id: String,
+    ///APIv2密钥，32字节
+    pub api_key: SecretString,
+    pub sign_type: SignType,
+    pub base_url: String,
+    client: OnceLock<HttpClient>,
+}
+
+impl Debug for WechatPayV2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WechatPayV2")
+            .field("appid", &self.appid)
+            .field("mch_id", &self.mch_id)
+            .field("api_key", &self.api_key)
+            .field("sign_type", &self.sign_type)
+            .field("base_url", &self.base_url)
+            .finish()
+    }
+}
+
+impl WechatPayV2 {
+    pub fn new<S: AsRef<str>>(appid: S, mch_id: S, api_key: S) -> Self {
+        Self {
+            appid: appid.as_ref().to_string(),
+            mch_id: mch_id.as_ref().to_string(),
+            api_key: SecretString::new(api_key.as_ref()),
+            sign_type: SignType::default(),
+            base_url: "https://api.mch.weixin.qq.com".to_string(),
+            client: OnceLock::new(),
+        }
+    }
+
+    pub fn with_sign_type(mut self, sign_type: SignType) -> Self {
+        self.sign_type = sign_type;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl AsRef<str>) -> Self {
+        self.base_url = base_url.as_ref().to_string();
+        self
+    }
+
+    /// 使用指定的HTTP客户端，需要商户API证书的接口必须传入配置了证书的客户端
+    pub fn with_client(mut self, client: HttpClient) -> Self {
+        self.client = OnceLock::from(client);
+        self
+    }
+
+    pub(crate) fn client(&self) -> &HttpClient {
+        self.client.get_or_init(HttpClient::new)
+    }
+
+    /// 生成请求报文，补全随机字符串和签名
+    pub(crate) fn build_xml<P: V2ParamsTrait>(
+        &self,
+        mut params: P,
+        sign_type: SignType,
+    ) -> Result<String, PayError> {
+        params.fill_defaults(self);
+        let mut map = xml::to_map(&params)?;
+        map.insert("nonce_str".to_string(), crate::util::random_trade_no());
+        if sign_type != SignType::Md5 {
+            map.insert("sign_type".to_string(), sign_type.as_str().to_string());
+        }
+        let signature = sign(&map, self.api_key.expose(), sign_type);
+        map.insert("sign".to_string(), signature);
+        Ok(xml::to_xml(&map))
+    }
+
+    /// 解析响应报文，通信失败时返回错误，`verify_sign`为true时校验签名
+    pub(crate) fn parse_xml<R: serde::de::DeserializeOwned>(
+        &self,
+        body: &str,
+        sign_type: SignType,
+        verify_sign: bool,
+    ) -> Result<R, PayError> {
+        let map = xml::from_xml(body)?;
+        if map.get("return_code").map(String::as_str) != Some("SUCCESS") {
+            return Err(PayError::WechatError(format!(
+                "{}: {}",
+                map.get("return_code")
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+                map.get("return_msg")
+                    .map(String::as_str)
+                    .unwrap_or_default()
+            )));
+        }
+        if verify_sign {
+            verify(&map, self.api_key.expose(), sign_type)?;
+        }
+        xml::from_map(map)
+    }
+
+    /// 解析并校验支付结果通知
+    pub fn decode_notify(&self, body: &str) -> Result<model::OrderQueryResponse, PayError> {
+        self.parse_xml(body, self.sign_type, true)
+    }
+
+    /// 应答支付结果通知
+    pub fn notify_response(success: bool, message: &str) -> String {
+        let mut map = BTreeMap::new();
+        let code = if success { "SUCCESS" } else { "FAIL" };
+        map.insert("return_code".to_string(), code.to_string());
+        map.insert("return_msg".to_string(), message.to_string());
+        xml::to_xml(&map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v2::model::OrderQueryResponse;
+    use crate::v2::{sign, xml, SignType, WechatPayV2};
+
+    const API_KEY: &str = "192006250b4c09247ec02edce69f6a2d";
+
+    #[test]
+    fn test_sign() {
+        let map = xml::from_xml(
+            "<xml><appid>wxd930ea5d5a258f4f</appid><mch_id>10000100</mch_id>\
+             <device_info>1000</device_info><body>test</body>\
+             <nonce_str>ibuaiVcKdpRxkhJA</nonce_str><attach></attach></xml>",
+        )
+        .unwrap();
+        assert_eq!(
+            sign(&map, API_KEY, SignType::Md5),
+            "9A0A8659F005D6984697E2CA0A9CF3B7"
+        );
+        assert_eq!(
+            sign(&map, API_KEY, SignType::HmacSha256),
+            "6A9AE1657590FD6257D693A078E1C3E4BB6BA4DC30B23E0EE2496E54170DACD6"
+        );
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let wechat_pay = WechatPayV2::new("wx2421b1c4370ec43b", "10000100", API_KEY);
+        let mut map = xml::from_xml(
+            "<xml><return_code>SUCCESS</return_code><result_code>SUCCESS</result_code>\
+             <appid>wx2421b1c4370ec43b</appid><mch_id>10000100</mch_id>\
+             <trade_state>SUCCESS</trade_state><total_fee>100</total_fee>\
+             <out_trade_no>1415757673</out_trade_no></xml>",
+        )
+        .unwrap();
+        let signature = sign(&map, API_KEY, SignType::Md5);
+        map.insert("sign".to_string(), signature);
+        let body = xml::to_xml(&map);
+        let response: OrderQueryResponse =
+            wechat_pay.parse_xml(&body, SignType::Md5, true).unwrap();
+        assert_eq!(response.total_fee, Some(100));
+        assert_eq!(response.trade_state.as_deref(), Some("SUCCESS"));
+
+        let tampered = body.replace("100]]>", "1]]>");
+        assert!(wechat_pay
+            .parse_xml::<OrderQueryResponse>(&tampered, SignType::Md5, true)
+            .is_err());
+        let failed = "<xml><return_code>FAIL</return_code><return_msg>签名错误</return_msg></xml>";
+        assert_eq!(
+            wechat_pay
+                .parse_xml::<OrderQueryResponse>(failed, SignType::Md5, true)
+                .unwrap_err()
+                .to_string(),
+            "pay error: FAIL: 签名错误"
+        );
+    }
+}
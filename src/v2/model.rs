@@ -0,0 +1,268 @@
+use crate::v2::xml::number_option;
+use crate::v2::{V2ParamsTrait, WechatPayV2};
+use serde::{Deserialize, Serialize};
+
+/// 统一下单
+#[derive(Serialize, Debug, Clone)]
+pub struct UnifiedOrderParams {
+    ///【公众账号ID】 不设置时使用WechatPayV2配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 不设置时使用WechatPayV2配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mch_id: Option<String>,
+    ///【设备号】 终端设备号(门店号或收银设备ID)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_info: Option<String>,
+    ///【商品描述】 商品简单描述
+    pub body: String,
+    ///【商品详情】 单品优惠字段，JSON字符串
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    ///【附加数据】 在查询API和支付通知中原样返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+    ///【商户订单号】 商户系统内部订单号，32个字符内
+    pub out_trade_no: String,
+    ///【标价币种】 默认人民币：CNY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_type: Option<String>,
+    ///【标价金额】 订单总金额，单位为分
+    pub total_fee: i64,
+    ///【终端IP】 用户端IP，Native支付填调用微信支付API的机器IP
+    pub spbill_create_ip: String,
+    ///【交易起始时间】 格式为yyyyMMddHHmmss
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_start: Option<String>,
+    ///【交易结束时间】 格式为yyyyMMddHHmmss
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_expire: Option<String>,
+    ///【订单优惠标记】 代金券或立减优惠功能的参数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goods_tag: Option<String>,
+    ///【通知地址】 异步接收微信支付结果通知的回调地址，不能携带参数
+    pub notify_url: String,
+    ///【交易类型】 JSAPI、NATIVE、APP、MWEB
+    pub trade_type: String,
+    ///【商品ID】 trade_type=NATIVE时必传
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_id: Option<String>,
+    ///【指定支付方式】 no_credit表示不能使用信用卡支付
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_pay: Option<String>,
+    ///【用户标识】 trade_type=JSAPI时必传
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub openid: Option<String>,
+    ///【场景信息】 H5支付必传，JSON字符串
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_info: Option<String>,
+}
+
+impl UnifiedOrderParams {
+    pub fn new<S: AsRef<str>>(
+        body: S,
+        out_trade_no: S,
+        total_fee: i64,
+        spbill_create_ip: S,
+        notify_url: S,
+        trade_type: S,
+    ) -> Self {
+        Self {
+            appid: None,
+            mch_id: None,
+            device_info: None,
+            body: body.as_ref().to_string(),
+            detail: None,
+            attach: None,
+            out_trade_no: out_trade_no.as_ref().to_string(),
+            fee_type: None,
+            total_fee,
+            spbill_create_ip: spbill_create_ip.as_ref().to_string(),
+            time_start: None,
+            time_expire: None,
+            goods_tag: None,
+            notify_url: notify_url.as_ref().to_string(),
+            trade_type: trade_type.as_ref().to_string(),
+            product_id: None,
+            limit_pay: None,
+            openid: None,
+            scene_info: None,
+        }
+    }
+}
+
+impl V2ParamsTrait for UnifiedOrderParams {
+    fn fill_defaults(&mut self, pay: &WechatPayV2) {
+        self.appid.get_or_insert_with(|| pay.appid.clone());
+        self.mch_id.get_or_insert_with(|| pay.mch_id.clone());
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UnifiedOrderResponse {
+    ///【业务结果】 SUCCESS/FAIL
+    pub result_code: Option<String>,
+    ///【错误代码】
+    pub err_code: Option<String>,
+    ///【错误代码描述】
+    pub err_code_des: Option<String>,
+    pub appid: Option<String>,
+    pub mch_id: Option<String>,
+    pub device_info: Option<String>,
+    ///【交易类型】
+    pub trade_type: Option<String>,
+    ///【预支付交易会话标识】 有效期为2小时
+    pub prepay_id: Option<String>,
+    ///【二维码链接】 trade_type=NATIVE时返回
+    pub code_url: Option<String>,
+    ///【支付跳转链接】 trade_type=MWEB时返回
+    pub mweb_url: Option<String>,
+}
+
+/// 查询订单，微信订单号和商户订单号二选一
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct OrderQueryParams {
+    ///【公众账号ID】 不设置时使用WechatPayV2配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid: Option<String>,
+    ///【商户号】 不设置时使用WechatPayV2配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mch_id: Option<String>,
+    ///【微信订单号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    ///【商户订单号】
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_trade_no: Option<String>,
+}
+
+impl OrderQueryParams {
+    pub fn by_transaction_id<S: AsRef<str>>(transaction_id: S) -> Self {
+        Self {
+            transaction_id: Some(transaction_id.as_ref().to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_out_trade_no<S: AsRef<str>>(out_trade_no: S) -> Self {
+        Self {
+            out_trade_no: Some(out_trade_no.as_ref().to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl V2ParamsTrait for OrderQueryParams {
+    fn fill_defaults(&mut self, pay: &WechatPayV2) {
+        self.appid.get_or_insert_with(|| pay.appid.clone());
+        self.mch_id.get_or_insert_with(|| pay.mch_id.clone());
+    }
+}
+
+/// 查询订单结果，也用于支付结果通知
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrderQueryResponse {
+    ///【业务结果】 SUCCESS/FAIL
+    pub result_code: Option<String>,
+    ///【错误代码】
+    pub err_code: Option<String>,
+    ///【错误代码描述】
+    pub err_code_des: Option<String>,
+    pub appid: Option<String>,
+    pub mch_id: Option<String>,
+    pub device_info: Option<String>,
+    ///【用户标识】
+    pub openid: Option<String>,
+    ///【是否关注公众账号】 Y/N
+    pub is_subscribe: Option<String>,
+    ///【交易类型】
+    pub trade_type: Option<String>,
+    ///【交易状态】 SUCCESS、REFUND、NOTPAY、CLOSED、REVOKED、USERPAYING、PAYERROR，支付通知中不返回
+    pub trade_state: Option<String>,
+    ///【付款银行】
+    pub bank_type: Option<String>,
+    ///【标价金额】 单位为分
+    #[serde(default, deserialize_with = "number_option::deserialize")]
+    pub total_fee: Option<i64>,
+    ///【应结订单金额】 去掉非充值代金券后的金额
+    #[serde(default, deserialize_with = "number_option::deserialize")]
+    pub settlement_total_fee: Option<i64>,
+    ///【标价币种】
+    pub fee_type: Option<String>,
+    ///【现金支付金额】
+    #[serde(default, deserialize_with = "number_option::deserialize")]
+    pub cash_fee: Option<i64>,
+    ///【微信支付订单号】
+    pub transaction_id: Option<String>,
+    ///【商户订单号】
+    pub out_trade_no: Option<String>,
+    ///【附加数据】
+    pub attach: Option<String>,
+    ///【支付完成时间】 格式为yyyyMMddHHmmss
+    pub time_end: Option<String>,
+    ///【交易状态描述】
+    pub trade_state_desc: Option<String>,
+}
+
+/// 发放现金红包，需要商户API证书，只支持MD5签名
+#[derive(Serialize, Debug, Clone)]
+pub struct SendRedPackParams {
+    ///【商户号】 不设置时使用WechatPayV2配置的商户号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mch_id: Option<String>,
+    ///【公众账号appid】 不设置时使用WechatPayV2配置的appid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wxappid: Option<String>,
+    ///【商户订单号】 组成：mch_id+yyyymmdd+10位一天内不能重复的数字
+    pub mch_billno: String,
+    ///【商户名称】 红包发送者名称
+    pub send_name: String,
+    ///【用户openid】 接受红包的用户在wxappid下的openid
+    pub re_openid: String,
+    ///【付款金额】 单位为分
+    pub total_amount: i64,
+    ///【红包发放总人数】 普通红包固定为1
+    pub total_num: i64,
+    ///【红包祝福语】
+    pub wishing: String,
+    ///【IP地址】 调用接口的机器IP地址
+    pub client_ip: String,
+    ///【活动名称】
+    pub act_name: String,
+    ///【备注】
+    pub remark: String,
+    ///【场景id】 发放红包使用场景，红包金额大于200或者小于1元时必传
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_id: Option<String>,
+    ///【活动信息】 urlencode后的风控信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_info: Option<String>,
+}
+
+impl V2ParamsTrait for SendRedPackParams {
+    fn fill_defaults(&mut self, pay: &WechatPayV2) {
+        self.wxappid.get_or_insert_with(|| pay.appid.clone());
+        self.mch_id.get_or_insert_with(|| pay.mch_id.clone());
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SendRedPackResponse {
+    ///【业务结果】 SUCCESS/FAIL，FAIL时使用原商户订单号重试
+    pub result_code: Option<String>,
+    ///【错误代码】
+    pub err_code: Option<String>,
+    ///【错误代码描述】
+    pub err_code_des: Option<String>,
+    ///【商户订单号】
+    pub mch_billno: Option<String>,
+    pub mch_id: Option<String>,
+    pub wxappid: Option<String>,
+    ///【用户openid】
+    pub re_openid: Option<String>,
+    ///【付款金额】 单位为分
+    #[serde(default, deserialize_with = "number_option::deserialize")]
+    pub total_amount: Option<i64>,
+    ///【微信单号】 红包订单的微信单号
+    pub send_listid: Option<String>,
+}
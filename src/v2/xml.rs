@@ -0,0 +1,174 @@
+//! APIv2的XML报文，所有字段都在`<xml>`根节点下，只有一层
+use crate::error::PayError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// 把参数结构体转换为字段表，`None`字段不输出，嵌套结构序列化为JSON字符串
+pub fn to_map<T: Serialize>(value: &T) -> Result<BTreeMap<String, String>, PayError> {
+    let object = match serde_json::to_value(value)? {
+        Value::Object(object) => object,
+        other => {
+            return Err(PayError::XmlError(format!(
+                "params must be a struct, got {}",
+                other
+            )))
+        }
+    };
+    let mut map = BTreeMap::new();
+    for (key, value) in object {
+        let value = match value {
+            Value::Null => continue,
+            Value::String(value) => value,
+            Value::Bool(value) => value.to_string(),
+            Value::Number(value) => value.to_string(),
+            value => value.to_string(),
+        };
+        map.insert(key, value);
+    }
+    Ok(map)
+}
+
+/// 把字段表转换为响应结构体，字段值都是字符串
+pub fn from_map<T: DeserializeOwned>(map: BTreeMap<String, String>) -> Result<T, PayError> {
+    let object = map
+        .into_iter()
+        .map(|(key, value)| (key, Value::String(value)))
+        .collect::<serde_json::Map<_, _>>();
+    Ok(serde_json::from_value(Value::Object(object))?)
+}
+
+/// 生成XML报文，字段值使用CDATA包裹
+pub fn to_xml(map: &BTreeMap<String, String>) -> String {
+    let mut xml = String::from("<xml>");
+    for (key, value) in map {
+        xml.push_str(&format!(
+            "<{}><![CDATA[{}]]></{}>",
+            key,
+            value.replace("]]>", "]]]]><![CDATA[>"),
+            key
+        ));
+    }
+    xml.push_str("</xml>");
+    xml
+}
+
+/// 解析XML报文为字段表，不支持DTD和多层嵌套
+pub fn from_xml(xml: &str) -> Result<BTreeMap<String, String>, PayError> {
+    let error = |message: &str| PayError::XmlError(message.to_string());
+    let mut rest = xml.trim();
+    if rest.starts_with("<?xml") {
+        let end = rest
+            .find("?>")
+            .ok_or_else(|| error("unclosed xml declaration"))?;
+        rest = rest[end + 2..].trim_start();
+    }
+    if rest.starts_with("<!") {
+        return Err(error("DTD is not allowed"));
+    }
+    rest = rest
+        .strip_prefix("<xml>")
+        .and_then(|rest| rest.trim_end().strip_suffix("</xml>"))
+        .ok_or_else(|| error("root element must be <xml>"))?;
+    let mut map = BTreeMap::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let tag_end = rest
+            .find('>')
+            .filter(|_| rest.starts_with('<'))
+            .ok_or_else(|| error("expected a start tag"))?;
+        let name = &rest[1..tag_end];
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
+            return Err(PayError::XmlError(format!("invalid tag <{}>", name)));
+        }
+        rest = &rest[tag_end + 1..];
+        let close = format!("</{}>", name);
+        let end = rest
+            .find(&close)
+            .ok_or_else(|| PayError::XmlError(format!("unclosed tag <{}>", name)))?;
+        map.insert(name.to_string(), text(&rest[..end])?);
+        rest = &rest[end + close.len()..];
+    }
+    Ok(map)
+}
+
+/// 元素内容，由普通文本和CDATA组成
+fn text(content: &str) -> Result<String, PayError> {
+    let mut text = String::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata
+                .find("]]>")
+                .ok_or_else(|| PayError::XmlError("unclosed CDATA".to_string()))?;
+            text.push_str(&cdata[..end]);
+            rest = &cdata[end + 3..];
+        } else {
+            let end = rest.find("<![CDATA[").unwrap_or(rest.len());
+            if rest[..end].contains('<') {
+                return Err(PayError::XmlError(
+                    "nested elements are not supported".to_string(),
+                ));
+            }
+            text.push_str(&unescape(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    Ok(text)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 数字字段在XML中是字符串，反序列化为`Option<i64>`
+pub(crate) mod number_option {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(value) if !value.is_empty() => {
+                value.parse().map(Some).map_err(serde::de::Error::custom)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v2::xml::{from_xml, to_xml};
+
+    #[test]
+    fn test_xml_round_trip() {
+        let xml = r#"<xml>
+           <return_code><![CDATA[SUCCESS]]></return_code>
+           <return_msg>OK &amp; done</return_msg>
+           <total_fee>1</total_fee>
+           <attach><![CDATA[a<b>]]></attach>
+        </xml>"#;
+        let map = from_xml(xml).unwrap();
+        assert_eq!(map["return_code"], "SUCCESS");
+        assert_eq!(map["return_msg"], "OK & done");
+        assert_eq!(map["total_fee"], "1");
+        assert_eq!(map["attach"], "a<b>");
+        assert_eq!(from_xml(&to_xml(&map)).unwrap(), map);
+
+        assert!(
+            from_xml(r#"<!DOCTYPE xml [<!ENTITY a SYSTEM "file:///etc/passwd">]><xml></xml>"#)
+                .is_err()
+        );
+        assert!(from_xml("<xml><a><b>1</b></a></xml>").is_err());
+    }
+}
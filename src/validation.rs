@@ -0,0 +1,290 @@
+use crate::error::ValidationError;
+use crate::model::AmountInfo;
+use crate::money::Money;
+
+/// 商品描述不超过127字节
+pub(crate) fn description(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if value.is_empty() {
+        errors.push(ValidationError::new(field, "must not be empty"));
+    } else if value.len() > 127 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be at most 127 bytes, got {}", value.len()),
+        ));
+    }
+}
+
+/// 商户订单号6-32个字符，只能是数字、大小写字母和_-|*
+pub(crate) fn out_trade_no(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if !(6..=32).contains(&value.len()) {
+        errors.push(ValidationError::new(
+            field,
+            format!("length must be between 6 and 32, got {}", value.len()),
+        ));
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '|' | '*'))
+    {
+        errors.push(ValidationError::new(
+            field,
+            "must only contain digits, letters and _-|*",
+        ));
+    }
+}
+
+/// 商家转账单号不超过32个字符，只能由数字、大小写字母组成
+pub(crate) fn out_bill_no(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if value.is_empty() || value.len() > 32 {
+        errors.push(ValidationError::new(
+            field,
+            format!("length must be between 1 and 32, got {}", value.len()),
+        ));
+    }
+    if !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+        errors.push(ValidationError::new(
+            field,
+            "must only contain digits and letters",
+        ));
+    }
+}
+
+/// 附加数据不超过128字节
+pub(crate) fn attach(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if value.len() > 128 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be at most 128 bytes, got {}", value.len()),
+        ));
+    }
+}
+
+/// 转账备注不超过32个字符
+pub(crate) fn transfer_remark(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    let count = value.chars().count();
+    if count == 0 || count > 32 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be between 1 and 32 characters, got {}", count),
+        ));
+    }
+}
+
+/// 投诉回复内容1-200个字符
+pub(crate) fn response_content(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    let count = value.chars().count();
+    if count == 0 || count > 200 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be between 1 and 200 characters, got {}", count),
+        ));
+    }
+}
+
+/// 支付分订单取消、修改原因1-50个字符
+pub(crate) fn service_order_reason(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    let count = value.chars().count();
+    if count == 0 || count > 50 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be between 1 and 50 characters, got {}", count),
+        ));
+    }
+}
+
+/// 代金券批次名称1-9个字符
+pub(crate) fn stock_name(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    let count = value.chars().count();
+    if count == 0 || count > 9 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be between 1 and 9 characters, got {}", count),
+        ));
+    }
+}
+
+/// 单次上传1-200个券code
+pub(crate) fn coupon_code_list(field: &str, value: &[String], errors: &mut Vec<ValidationError>) {
+    if value.is_empty() || value.len() > 200 {
+        errors.push(ValidationError::new(
+            field,
+            format!("must contain 1 to 200 codes, got {}", value.len()),
+        ));
+    }
+}
+
+/// 订单金额必须大于0
+pub(crate) fn amount(field: &str, value: &AmountInfo, errors: &mut Vec<ValidationError>) {
+    money(field, &value.total, errors)
+}
+
+/// 金额必须大于0
+pub(crate) fn money(field: &str, value: &Money, errors: &mut Vec<ValidationError>) {
+    if !value.is_positive() {
+        errors.push(ValidationError::new(
+            field,
+            format!("must be greater than 0, got {}", value.cents()),
+        ));
+    }
+}
+
+/// 通知地址必须为https，且不能携带参数
+pub(crate) fn notify_url(field: &str, value: &str, errors: &mut Vec<ValidationError>) {
+    if !value.starts_with("https://") {
+        errors.push(ValidationError::new(field, "must be an https url"));
+    }
+    if value.contains('?') {
+        errors.push(ValidationError::new(
+            field,
+            "must not contain query parameters",
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PayError;
+    use crate::model::ParamsTrait;
+    use crate::model::{
+        CodepayParams, CodepaySceneInfo, NativeParams, TransferBillsParams, TransferSceneReportInfo,
+    };
+    use crate::pay::WechatPay;
+    use std::time::Duration;
+
+    #[test]
+    fn test_native_builder() {
+        let params = NativeParams::builder()
+            .appid("wxd678efh567hg6787")
+            .mchid("1230000109")
+            .description("测试支付1分")
+            .notify_url("https://mydomain.com/pay/notify")
+            .out_trade_no("1217752501201407033233368018")
+            .amount(1)
+            .attach("自定义数据")
+            .expire_in(Duration::from_secs(15 * 60))
+            .build()
+            .expect("valid params");
+        assert_eq!(params.attach.as_deref(), Some("自定义数据"));
+        let json: serde_json::Value = serde_json::from_str(&params.to_json()).unwrap();
+        let time_expire = json["time_expire"].as_str().unwrap();
+        assert_eq!(time_expire.len(), "2015-05-20T13:29:35+08:00".len());
+        assert!(time_expire.ends_with("+08:00"));
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_builder_lists_every_violation() {
+        let error = NativeParams::builder()
+            .description("长".repeat(43))
+            .notify_url("http://mydomain.com/notify?a=1")
+            .out_trade_no("12#4")
+            .amount(0)
+            .attach("a".repeat(129))
+            .build()
+            .unwrap_err();
+        let errors = match error {
+            PayError::Validation(errors) => errors,
+            other => panic!("unexpected error: {}", other),
+        };
+        let fields = errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                "description",
+                "out_trade_no",
+                "out_trade_no",
+                "amount",
+                "notify_url",
+                "notify_url",
+                "attach",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fill_defaults() {
+        let wechat_pay = WechatPay::new(
+            "wxd678efh567hg6787",
+            "1230000109",
+            "",
+            "",
+            "",
+            "https://mydomain.com/pay/notify",
+        );
+        let mut params = NativeParams::new("测试支付1分", "1243243", 1.into());
+        params.mchid = Some("1900000001".to_string());
+        params.fill_defaults(&wechat_pay);
+        assert_eq!(params.appid.as_deref(), Some("wxd678efh567hg6787"));
+        assert_eq!(params.mchid.as_deref(), Some("1900000001"));
+        assert_eq!(
+            params.notify_url.as_deref(),
+            Some("https://mydomain.com/pay/notify")
+        );
+
+        let mut params = TransferBillsParams::builder()
+            .out_bill_no("plfk2020042013")
+            .transfer_scene_id("1000")
+            .openid("o-MYE42l80oelYMDE34nYD456Xoy")
+            .transfer_amount(100)
+            .transfer_remark("新会员开通有礼")
+            .transfer_scene_report_infos(vec![])
+            .build()
+            .unwrap();
+        params.fill_defaults(&wechat_pay);
+        let json: serde_json::Value = serde_json::from_str(&params.to_json()).unwrap();
+        assert_eq!(json["appid"], "wxd678efh567hg6787");
+        assert!(json.get("mchid").is_none());
+    }
+
+    #[test]
+    fn test_transfer_bills_builder() {
+        let error = TransferBillsParams::builder()
+            .appid("wxd678efh567hg6787")
+            .out_bill_no("plfk2020042013")
+            .transfer_scene_id("1000")
+            .openid("o-MYE42l80oelYMDE34nYD456Xoy")
+            .transfer_amount(0)
+            .notify_url("https://mydomain.com/transfer/notify")
+            .transfer_remark("新会员开通有礼")
+            .transfer_scene_report_infos(vec![TransferSceneReportInfo {
+                info_type: "活动名称".to_string(),
+                info_content: "新会员有礼".to_string(),
+            }])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "validation error: transfer_amount: must be greater than 0, got 0"
+        );
+    }
+
+    #[test]
+    fn test_codepay_builder() {
+        let builder = CodepayParams::builder()
+            .description("image形象店-深圳腾大-QQ公仔")
+            .out_trade_no("1217752501201407033233368018")
+            .amount(1)
+            .scene_info(CodepaySceneInfo::new("1234"));
+        let params = builder
+            .clone()
+            .payer("130061098828009406")
+            .build()
+            .expect("valid params");
+        let json: serde_json::Value = serde_json::from_str(&params.to_json()).unwrap();
+        assert_eq!(json["payer"]["auth_code"], "130061098828009406");
+        assert_eq!(json["scene_info"]["store_info"]["out_id"], "1234");
+        assert!(json.get("notify_url").is_none());
+
+        let error = builder
+            .payer("oUpF8uMuAJO_M2pxb1Q9zNjWeS6o")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "validation error: payer: auth_code must be 18 digits starting with 10-15"
+        );
+    }
+}